@@ -4,7 +4,9 @@ use std::io::{Read, Write};
 use std::process;
 use std::{thread, time};
 use trello::Renderable;
-use trello::{Card, CardContents, TrelloClient, TrelloError, TrelloObject};
+use trello::{
+    Card, CardContents, Checklist, Label, LabelColor, TrelloClient, TrelloError, TrelloObject,
+};
 
 pub fn multiselect_trello_object<T: TrelloObject + Renderable + PartialEq>(
     objects: &[T],
@@ -39,6 +41,57 @@ pub fn select_trello_object<T: TrelloObject + Renderable>(
     Ok(result)
 }
 
+/// Asks the user a yes or no question, defaulting to no. Always answers no when
+/// there is no user attending the terminal.
+pub fn confirm(text: &str) -> Result<bool, std::io::Error> {
+    if !console::user_attended() {
+        return Ok(false);
+    }
+    dialoguer::Confirm::new()
+        .with_prompt(text)
+        .default(false)
+        .interact()
+}
+
+/// Lets the user pick a label color from the palette. Returns None when
+/// "no color" is picked.
+pub fn select_label_color() -> Result<Option<LabelColor>, std::io::Error> {
+    let mut items = vec![String::from("no color")];
+    items.extend(LabelColor::PALETTE.iter().map(|c| {
+        let swatch = Label::new("", "", c.as_str()).simple_render();
+        format!("{} {}", swatch, c)
+    }));
+
+    let index = dialoguer::Select::new()
+        .items(&items)
+        .default(0)
+        .with_prompt("Select Label color")
+        .interact()?;
+
+    Ok(index.checked_sub(1).map(|i| LabelColor::PALETTE[i].clone()))
+}
+
+/// Opens the given url in the default browser of the user. Returns whether a
+/// browser could be started.
+pub fn open_in_browser(url: &str) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        process::Command::new("xdg-open")
+    };
+
+    command
+        .arg(url)
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
 pub fn get_input(text: &str) -> Result<String, rustyline::error::ReadlineError> {
     let mut rl = rustyline::Editor::<()>::new();
     rl.bind_sequence(
@@ -56,12 +109,35 @@ pub fn get_input(text: &str) -> Result<String, rustyline::error::ReadlineError>
     rl.readline(text)
 }
 
+/// Opens the users chosen editor (specified by the $EDITOR environment variable)
+/// with the initial text provided, and returns the contents of the file once the
+/// editor has been closed. Trailing whitespace is removed.
+pub fn get_text_from_editor(initial: &str) -> Result<String, Box<dyn Error>> {
+    let mut file = tempfile::Builder::new().suffix(".md").tempfile()?;
+    let editor_env = env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
+
+    debug!("Using editor: {}", editor_env);
+
+    write!(file, "{}", initial)?;
+
+    let status = process::Command::new(&editor_env)
+        .arg(file.path())
+        .status()?;
+    debug!("Editor closed with code: {}", status);
+
+    let mut buf = String::new();
+    file.reopen()?.read_to_string(&mut buf)?;
+
+    Ok(buf.trim_end().to_string())
+}
+
 /// Opens the users chosen editor (specified by the $EDITOR environment variable)
 /// to edit a specified card. If $EDITOR is not set, the default editor will fallback
 /// to vi.
 ///
 /// This function will upload any changes written by the editor to Trello. This includes
-/// when the editor is not closed but content is saved.
+/// when the editor is not closed but content is saved. Checklists are presented as
+/// markdown task lists, so ticking an item in the editor will complete it in Trello.
 pub fn edit_card(client: &TrelloClient, card: &Card) -> Result<(), Box<dyn Error>> {
     let mut file = tempfile::Builder::new().suffix(".md").tempfile()?;
     let editor_env = env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
@@ -69,9 +145,20 @@ pub fn edit_card(client: &TrelloClient, card: &Card) -> Result<(), Box<dyn Error
     debug!("Using editor: {}", editor_env);
     debug!("Editing card: {:?}", card);
 
-    writeln!(file, "{}", card.render(true))?;
-
     let mut new_card = card.clone();
+    if new_card.checklists.is_none() {
+        new_card.checklists = Some(Checklist::get_all(client, &card.id)?);
+    }
+    if new_card.custom_fields.is_none() {
+        new_card.retrieve_custom_fields(client)?;
+    }
+
+    if client.is_read_only() {
+        println!("{}", new_card.render(true));
+        return Ok(());
+    }
+
+    writeln!(file, "{}", new_card.render(true))?;
 
     // Outer retry loop - reopen editor if last upload attempt failed
     loop {
@@ -112,12 +199,13 @@ pub fn edit_card(client: &TrelloClient, card: &Card) -> Result<(), Box<dyn Error
                 || matches!(result, Some(Err(_)))
                 || new_card.name != contents.name
                 || new_card.desc != contents.desc
+                || Checklist::differs(
+                    new_card.checklists.as_deref().unwrap_or_default(),
+                    &contents.checklists,
+                )
             {
-                new_card.name = contents.name;
-                new_card.desc = contents.desc;
-
                 debug!("Updating card: {:?}", new_card);
-                result = Some(Card::update(client, &new_card));
+                result = Some(upload_card(client, &mut new_card, contents));
 
                 match &result {
                     Some(Ok(_)) => debug!("Updated card"),
@@ -152,3 +240,29 @@ pub fn edit_card(client: &TrelloClient, card: &Card) -> Result<(), Box<dyn Error
 
     Ok(())
 }
+
+/// Uploads the contents parsed from the editor. The card is updated in place to
+/// reflect what is now stored in Trello.
+fn upload_card(
+    client: &TrelloClient,
+    card: &mut Card,
+    contents: CardContents,
+) -> Result<Card, TrelloError> {
+    card.name = contents.name;
+    card.desc = contents.desc;
+
+    let updated = Card::update(client, card)?;
+
+    let current = card.checklists.clone().unwrap_or_default();
+    if Checklist::differs(&current, &contents.checklists) {
+        debug!("Updating checklists: {:?}", contents.checklists);
+        card.checklists = Some(Checklist::sync(
+            client,
+            &card.id,
+            &current,
+            &contents.checklists,
+        )?);
+    }
+
+    Ok(updated)
+}
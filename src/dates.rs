@@ -0,0 +1,126 @@
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday,
+};
+use regex::Regex;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Error)]
+pub enum DateError {
+    #[error("Invalid date: {0}")]
+    Invalid(String),
+}
+
+/// Time of day used when a date is specified without a time
+const DEFAULT_TIME: (u32, u32) = (12, 0);
+
+fn invalid(input: &str) -> DateError {
+    DateError::Invalid(format!(
+        "Unable to understand '{}'. Try something like '2020-06-28 17:00', 'tomorrow', '+3d' or 'next friday 17:00'",
+        input
+    ))
+}
+
+fn to_local(input: &str, datetime: NaiveDateTime) -> Result<DateTime<Local>, DateError> {
+    // earliest() resolves ambiguous times during daylight saving transitions
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .ok_or_else(|| invalid(input))
+}
+
+fn parse_absolute(input: &str) -> Option<NaiveDateTime> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Some(datetime.with_timezone(&Local).naive_local());
+    }
+
+    for format in &["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(input, format) {
+            return Some(datetime);
+        }
+    }
+
+    let (hour, minute) = DEFAULT_TIME;
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(hour, minute, 0))
+}
+
+fn parse_offset(token: &str) -> Option<Duration> {
+    let re = Regex::new(r"^([+-]\d+)([mhdw])$").expect("Invalid offset regex");
+    let captures = re.captures(token)?;
+
+    let amount: i64 = captures[1].parse().ok()?;
+    match &captures[2] {
+        "m" => Some(Duration::minutes(amount)),
+        "h" => Some(Duration::hours(amount)),
+        "d" => Some(Duration::days(amount)),
+        "w" => Some(Duration::weeks(amount)),
+        _ => None,
+    }
+}
+
+/// Returns the date of the next given weekday strictly after the specified date.
+fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+    let days = if days == 0 { 7 } else { days };
+
+    date + Duration::days(i64::from(days))
+}
+
+/// Parses a due date entered by the user, relative to the `now` provided.
+/// Dates are interpreted in the local timezone.
+///
+/// Supported formats include:
+/// * Absolute dates such as `2020-06-28`, `2020-06-28 17:00` or RFC 3339 timestamps
+/// * `now`, `today`, `tomorrow` and `yesterday`
+/// * Weekdays such as `friday` or `next friday`
+/// * Offsets from now such as `+3d`, `-2h`, `+1w` or `+30m`
+///
+/// Relative dates may be followed by a time such as `17:00`. Dates which are not
+/// given a time default to midday.
+pub fn parse_due(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>, DateError> {
+    let input = input.trim();
+
+    if let Some(datetime) = parse_absolute(input) {
+        return to_local(input, datetime);
+    }
+
+    let lowered = input.to_lowercase();
+    let mut tokens = lowered.split_whitespace().collect::<Vec<&str>>();
+
+    let time = match tokens.last() {
+        Some(token) => NaiveTime::parse_from_str(token, "%H:%M").ok(),
+        None => return Err(invalid(input)),
+    };
+    if time.is_some() {
+        tokens.pop();
+    }
+
+    let (hour, minute) = DEFAULT_TIME;
+    let midday = NaiveTime::from_hms_opt(hour, minute, 0).expect("Invalid default time");
+    let today = now.date_naive();
+
+    let datetime = match tokens.as_slice() {
+        [] if time.is_some() => today.and_time(midday),
+        ["now"] => now.naive_local(),
+        ["today"] => today.and_time(midday),
+        ["tomorrow"] => (today + Duration::days(1)).and_time(midday),
+        ["yesterday"] => (today - Duration::days(1)).and_time(midday),
+        ["next", day] | [day] if day.parse::<Weekday>().is_ok() => {
+            let weekday = day.parse::<Weekday>().map_err(|_| invalid(input))?;
+            next_weekday(today, weekday).and_time(midday)
+        }
+        [offset] => {
+            let offset = parse_offset(offset).ok_or_else(|| invalid(input))?;
+            now.naive_local() + offset
+        }
+        _ => return Err(invalid(input)),
+    };
+
+    let datetime = match time {
+        Some(time) => datetime.date().and_time(time),
+        None => datetime,
+    };
+
+    to_local(input, datetime)
+}
@@ -0,0 +1,37 @@
+use std::path::{Path, PathBuf};
+
+/// Makes a name received from Trello safe to use as a file name by replacing
+/// path separators, so that a file can never be written outside of its directory
+pub fn sanitize_filename(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | '\0' => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    match name.trim() {
+        "" | "." | ".." => String::from("attachment"),
+        _ => name,
+    }
+}
+
+/// Path of a file called `name` within `dir` which does not exist yet. When the
+/// name is taken, a number is appended before the extension, e.g. "notes (1).txt".
+pub fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() {
+        return path;
+    }
+
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => name.split_at(index),
+        _ => (name, ""),
+    };
+
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|p| !p.exists())
+        .expect("Ran out of file names")
+}
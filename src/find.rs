@@ -1,8 +1,9 @@
+use crate::cli;
 use clap::ArgMatches;
-use regex::RegexBuilder;
+use regex::{Regex, RegexBuilder};
 use std::cmp::Ordering;
 use thiserror::Error;
-use trello::{Board, Card, List, TrelloClient, TrelloObject};
+use trello::{Board, Card, Label, List, Renderable, TrelloClient, TrelloError, TrelloObject};
 
 #[derive(Debug, PartialEq, Error)]
 pub enum FindError {
@@ -14,27 +15,112 @@ pub enum FindError {
     NotFound(String),
     #[error("Wildcard error: {0}")]
     WildCard(String),
+    #[error("Invalid reference: {0}")]
+    Reference(String),
+    #[error("Unable to prompt for a selection: {0}")]
+    Prompt(String),
+}
+
+/// The ways in which an object can be referred to on the command line, besides
+/// its name
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reference<'a> {
+    /// Short link taken from the url of a board, e.g. https://trello.com/b/AbCd1234/my-board
+    BoardUrl(&'a str),
+    /// Short link taken from the url of a card, e.g. https://trello.com/c/AbCd1234/12-my-card
+    CardUrl(&'a str),
+    /// The 24 character hexadecimal id of an object
+    Id(&'a str),
+    /// Number of a card within its board, e.g. #42
+    CardNumber(u64),
+    /// A name pattern. Eight alphanumeric characters could also be a short link.
+    Name(&'a str),
+}
+
+impl<'a> Reference<'a> {
+    pub fn parse(value: &'a str) -> Reference<'a> {
+        let url = Regex::new(r"^https?://trello\.com/([bc])/([[:alnum:]]+)(/.*)?$")
+            .expect("Invalid url regex");
+
+        if let Some(captures) = url.captures(value) {
+            let short_link = captures.get(2).map_or("", |m| m.as_str());
+            return match &captures[1] {
+                "b" => Reference::BoardUrl(short_link),
+                _ => Reference::CardUrl(short_link),
+            };
+        }
+        if value.len() == 24 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Reference::Id(value);
+        }
+        if let Some(number) = value.strip_prefix('#').and_then(|n| n.parse().ok()) {
+            return Reference::CardNumber(number);
+        }
+        Reference::Name(value)
+    }
+
+    /// Names which could also be the short link of a board or card
+    fn short_link(&self) -> Option<&'a str> {
+        match self {
+            Reference::Name(name)
+                if name.len() == 8 && name.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Searches through a collection of Trello objects and tries
 /// to match one and only one object to the name pattern provided.
 /// * If no matches are found, an Error is returned
-/// * If more than match is found, an Error is returned
+/// * If more than match is found, an Error is returned unless one of them is
+///   matched exactly, in full or from the start of its name
 /// * If only one item is matched, then it is returned
 pub fn get_object_by_name<'a, T: TrelloObject>(
     objects: &'a [T],
     name: &str,
     ignore_case: bool,
 ) -> Result<&'a T, FindError> {
-    let re = RegexBuilder::new(name)
-        .case_insensitive(ignore_case)
-        .build()?;
+    let objects = find_objects(objects, name, ignore_case, false)?;
+    single_object(objects, name)
+}
 
-    let mut objects = objects
-        .iter()
-        .filter(|o| re.is_match(o.get_name()))
-        .collect::<Vec<&T>>();
+/// Like get_object_by_name, with fuzzy matching when enabled in the params.
+/// When a name is ambiguous and the params allow it, the user is prompted to
+/// pick one of the matching objects instead.
+pub fn select_object_by_name<'a, T: TrelloObject + Renderable + Clone>(
+    objects: &'a [T],
+    name: &str,
+    params: &TrelloParams,
+) -> Result<&'a T, FindError> {
+    let objects = find_objects(objects, name, params.ignore_case, params.fuzzy)?;
+    select_object(objects, name, params)
+}
+
+/// Selects the single object among those matching a name, prompting for one when
+/// several match and prompts are enabled
+fn select_object<'a, T: TrelloObject + Renderable + Clone>(
+    objects: Vec<&'a T>,
+    name: &str,
+    params: &TrelloParams,
+) -> Result<&'a T, FindError> {
+    if objects.len() > 1 && params.prompt {
+        let options = objects.iter().map(|o| (*o).clone()).collect::<Vec<T>>();
+        eprintln!("'{}' matches more than one {}", name, T::get_type());
+        let selected =
+            cli::select_trello_object(&options).map_err(|e| FindError::Prompt(e.to_string()))?;
+        if let Some(index) = selected {
+            return Ok(objects[index]);
+        }
+    }
+    single_object(objects, name)
+}
 
+fn single_object<'a, T: TrelloObject>(
+    mut objects: Vec<&'a T>,
+    name: &str,
+) -> Result<&'a T, FindError> {
     match objects.len().cmp(&1) {
         Ordering::Equal => Ok(objects.remove(0)),
         Ordering::Greater => {
@@ -59,6 +145,125 @@ pub fn get_object_by_name<'a, T: TrelloObject>(
     }
 }
 
+/// Finds the objects matching a name. When several objects match, only the best
+/// matches are returned: objects named exactly as the name are preferred over those
+/// matching the pattern in full, and those over objects whose names start with it.
+fn find_objects<'a, T: TrelloObject>(
+    objects: &'a [T],
+    name: &str,
+    ignore_case: bool,
+    fuzzy: bool,
+) -> Result<Vec<&'a T>, FindError> {
+    let exact = |o: &&T| match ignore_case {
+        true => o.get_name().to_lowercase() == name.to_lowercase(),
+        false => o.get_name() == name,
+    };
+
+    if fuzzy {
+        let matches = objects
+            .iter()
+            .filter_map(|o| fuzzy_score(name, o.get_name(), ignore_case).map(|s| (o, s)))
+            .collect::<Vec<(&T, i64)>>();
+        if let Some((object, _)) = matches.iter().find(|(o, _)| exact(o)) {
+            return Ok(vec![*object]);
+        }
+        // As in fzf, ties are broken in favour of shorter names
+        let best = matches
+            .iter()
+            .map(|(o, score)| (*score, -(o.get_name().chars().count() as i64)))
+            .max();
+        return Ok(matches
+            .iter()
+            .filter(|(o, score)| Some((*score, -(o.get_name().chars().count() as i64))) == best)
+            .map(|(o, _)| *o)
+            .collect());
+    }
+
+    let regex = |pattern: &str| {
+        RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+    };
+    let re = regex(name)?;
+    let matches = objects
+        .iter()
+        .filter(|o| re.is_match(o.get_name()))
+        .collect::<Vec<&T>>();
+    if matches.len() <= 1 {
+        return Ok(matches);
+    }
+
+    let full = regex(&format!("^(?:{})$", name))?;
+    let prefix = regex(&format!("^(?:{})", name))?;
+    let tiers: [&dyn Fn(&&T) -> bool; 3] = [&exact, &|o| full.is_match(o.get_name()), &|o| {
+        prefix.is_match(o.get_name())
+    }];
+    for tier in tiers {
+        let preferred = matches
+            .iter()
+            .copied()
+            .filter(|o| tier(o))
+            .collect::<Vec<&T>>();
+        if preferred.len() == 1 {
+            return Ok(preferred);
+        }
+    }
+    Ok(matches)
+}
+
+/// Scores how well a pattern matches a name in the same way as fzf: every character
+/// of the pattern must appear in the name in order. Consecutive characters and
+/// characters at the start of words score higher, while gaps between them are
+/// penalised. Returns None when the name does not match.
+pub fn fuzzy_score(pattern: &str, name: &str, ignore_case: bool) -> Option<i64> {
+    let fold = |c: char| match ignore_case {
+        true => c.to_lowercase().next().unwrap_or(c),
+        false => c,
+    };
+    let mut pattern = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold)
+        .peekable();
+
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    let mut started = false;
+    for c in name.chars() {
+        let Some(&expected) = pattern.peek() else {
+            break;
+        };
+        if fold(c) == expected {
+            pattern.next();
+            score += 16;
+            if previous_matched {
+                score += 8;
+            }
+            let word_start = match previous {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+            };
+            if word_start {
+                score += 8;
+            }
+            started = true;
+            previous_matched = true;
+        } else {
+            if started {
+                score -= 1;
+            }
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+
+    match pattern.peek() {
+        None => Some(score),
+        Some(_) => None,
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct TrelloResult {
     pub board: Option<Board>,
@@ -66,12 +271,32 @@ pub struct TrelloResult {
     pub card: Option<Card>,
 }
 
-#[derive(Debug, PartialEq)]
+impl TrelloResult {
+    /// Retrieves the cards of the board and list, which are only retrieved by
+    /// `get_trello_object` when a card is looked up
+    pub fn retrieve_cards(&mut self, client: &TrelloClient) -> Result<(), TrelloError> {
+        if let Some(board) = &mut self.board {
+            board.retrieve_nested(client)?;
+            if let Some(list) = &mut self.list
+                && let Some(retrieved) = board.lists.iter().flatten().find(|l| l.id == list.id)
+            {
+                list.cards = retrieved.cards.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct TrelloParams<'a> {
     pub board_name: Option<&'a str>,
     pub list_name: Option<&'a str>,
     pub card_name: Option<&'a str>,
     pub ignore_case: bool,
+    /// Match names as fzf does rather than as regular expressions
+    pub fuzzy: bool,
+    /// Let the user pick an object when a name matches more than one
+    pub prompt: bool,
 }
 
 pub fn get_trello_params<'a>(matches: &'a ArgMatches) -> TrelloParams<'a> {
@@ -80,12 +305,183 @@ pub fn get_trello_params<'a>(matches: &'a ArgMatches) -> TrelloParams<'a> {
         list_name: matches.value_of("list_name"),
         card_name: matches.value_of("card_name"),
         ignore_case: !matches.is_present("case_sensitive"),
+        fuzzy: matches.is_present("fuzzy"),
+        prompt: console::user_attended(),
     }
 }
 
+/// Retrieves a card given its url, short link or id, along with its board and list.
+/// Archived cards are returned without a list.
+fn get_card_by_id(
+    client: &TrelloClient,
+    card_id: &str,
+) -> Result<TrelloResult, Box<dyn std::error::Error>> {
+    let card = Card::get(client, card_id)?;
+    let mut board = Board::get(client, &card.id_board)?;
+    board.retrieve_nested(client)?;
+
+    let list = board
+        .lists
+        .iter()
+        .flatten()
+        .find(|l| l.cards.iter().flatten().any(|c| c.id == card.id))
+        .cloned();
+    // prefer the card retrieved with the board, which has its members populated
+    let card = list
+        .iter()
+        .flat_map(|l| l.cards.iter().flatten())
+        .find(|c| c.id == card.id)
+        .cloned()
+        .unwrap_or(card);
+
+    Ok(TrelloResult {
+        board: Some(board),
+        list,
+        card: Some(card),
+    })
+}
+
+pub fn get_board(
+    client: &TrelloClient,
+    board_name: &str,
+    params: &TrelloParams,
+) -> Result<Board, Box<dyn std::error::Error>> {
+    let reference = Reference::parse(board_name);
+    match reference {
+        Reference::BoardUrl(id) | Reference::Id(id) => return Ok(Board::get(client, id)?),
+        Reference::CardUrl(_) | Reference::CardNumber(_) => {
+            return Err(Box::new(FindError::Reference(format!(
+                "'{}' refers to a card rather than a board",
+                board_name
+            ))));
+        }
+        Reference::Name(_) => {}
+    }
+
+    if board_name == "-" || board_name == "**" {
+        return Err(Box::new(FindError::WildCard(
+            "Board name must be specified unless the card is given by its url, short link or id"
+                .to_string(),
+        )));
+    }
+
+    let boards = Board::get_all(client)?;
+    match select_object_by_name(&boards, board_name, params) {
+        Ok(board) => Ok(board.clone()),
+        Err(FindError::NotFound(message)) => match reference.short_link() {
+            Some(short_link) => match Board::get(client, short_link) {
+                Err(TrelloError::NotFound(_)) => Err(Box::new(FindError::NotFound(message))),
+                result => Ok(result?),
+            },
+            None => Err(Box::new(FindError::NotFound(message))),
+        },
+        Err(e) => Err(Box::new(e)),
+    }
+}
+
+pub fn get_list<'a>(
+    lists: &'a [List],
+    list_name: &str,
+    params: &TrelloParams,
+) -> Result<&'a List, FindError> {
+    match Reference::parse(list_name) {
+        Reference::Id(id) => lists
+            .iter()
+            .find(|l| l.id == id)
+            .ok_or_else(|| FindError::NotFound(format!("List with id '{}' not found", id))),
+        _ => select_object_by_name(lists, list_name, params),
+    }
+}
+
+pub fn get_card<'a>(
+    cards: &'a [Card],
+    card_name: &str,
+    params: &TrelloParams,
+) -> Result<&'a Card, FindError> {
+    // matches every card, so that a single card can be selected with the usual prompt
+    if card_name == "**" {
+        return select_object(cards.iter().collect(), card_name, params);
+    }
+
+    let reference = Reference::parse(card_name);
+    if let Reference::CardNumber(number) = reference {
+        return cards
+            .iter()
+            .find(|c| c.id_short == Some(number))
+            .ok_or_else(|| FindError::NotFound(format!("Card #{} not found", number)));
+    }
+
+    match select_object_by_name(cards, card_name, params) {
+        Err(FindError::NotFound(message)) => {
+            let short_link = reference.short_link().map(|s| format!("/c/{}/", s));
+            short_link
+                .and_then(|s| cards.iter().find(|c| c.url.contains(&s)))
+                .ok_or(FindError::NotFound(message))
+        }
+        result => result,
+    }
+}
+
+/// Finds the board, list and card referred to by the given params. A lookup which
+/// fails after reading cached objects is retried with fresh objects from Trello,
+/// as the cache may predate the objects being created or renamed.
 pub fn get_trello_object(
     client: &TrelloClient,
     params: &TrelloParams,
+) -> Result<TrelloResult, Box<dyn std::error::Error>> {
+    match find_trello_object(client, params) {
+        Err(e) if client.served_from_cache() && is_not_found(e.as_ref()) => {
+            debug!("Lookup failed with cached objects ({}), retrying", e);
+            client.invalidate_cache();
+            find_trello_object(client, params)
+        }
+        result => result,
+    }
+}
+
+/// Gets the labels of a board in order to look up the given label names. Labels
+/// retrieved along with the board are used when available. Labels read from the cache
+/// are fetched from Trello again when any of the names is missing from them.
+pub fn get_labels<'a>(
+    client: &TrelloClient,
+    board: &Board,
+    names: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<Label>, TrelloError> {
+    let labels = match &board.labels {
+        Some(labels) => labels.clone(),
+        None => Label::get_all(client, &board.id)?,
+    };
+
+    let missing = |labels: &[Label]| {
+        names.into_iter().any(|name| {
+            matches!(
+                get_object_by_name(labels, name, true),
+                Err(FindError::NotFound(_))
+            )
+        })
+    };
+    if client.served_from_cache() && missing(&labels) {
+        debug!("Labels missing from cached labels, retrieving them again");
+        client.invalidate_cache();
+        return Label::get_all(client, &board.id);
+    }
+
+    Ok(labels)
+}
+
+fn is_not_found(error: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(error.downcast_ref(), Some(FindError::NotFound(_)))
+        || matches!(error.downcast_ref(), Some(TrelloError::NotFound(_)))
+}
+
+/// Retrieves the board, list and card matching the given parameters. Besides
+/// name patterns, boards can be given by their url, short link or id and cards by
+/// their url, short link, id or number within the board (e.g. #42). Cards given by
+/// their url or id are retrieved directly, regardless of the board and list.
+/// Cards are only retrieved when a card is looked up, see `TrelloResult::retrieve_cards`.
+fn find_trello_object(
+    client: &TrelloClient,
+    params: &TrelloParams,
 ) -> Result<TrelloResult, Box<dyn std::error::Error>> {
     let board_name = match params.board_name {
         Some(bn) => bn,
@@ -97,68 +493,167 @@ pub fn get_trello_object(
             });
         }
     };
-    let boards = Board::get_all(client)?;
-    let mut board = get_object_by_name(&boards, board_name, params.ignore_case)?.clone();
 
-    // This should retrieve everything at once
-    // This means better performance as it's less HTTP requests. But it does
-    // mean we might retrieve more than we actually need in memory.
-    board.retrieve_nested(client)?;
+    let params = TrelloParams {
+        fuzzy: params.fuzzy || client.config.fuzzy,
+        ..*params
+    };
 
-    if let Some("-") = params.list_name {
-        if let Some(card_name) = params.card_name {
-            let board_out = board.clone();
-            let lists = board
-                .lists
-                .ok_or("Board lists not populated after retrieve_nested")?;
+    if let Reference::CardUrl(card_id) = Reference::parse(board_name) {
+        if [params.list_name, params.card_name]
+            .iter()
+            .flatten()
+            .any(|name| *name != "-" && *name != "**")
+        {
+            return Err(Box::new(FindError::Reference(
+                "List and card names cannot be specified along with the url of a card".to_string(),
+            )));
+        }
+        return get_card_by_id(client, card_id);
+    }
 
+    if let Some(card_name) = params.card_name {
+        let reference = Reference::parse(card_name);
+        match reference {
+            Reference::CardUrl(card_id) | Reference::Id(card_id) => {
+                return get_card_by_id(client, card_id);
+            }
+            _ => {
+                if let Some(short_link) = reference.short_link()
+                    && (board_name == "-" || board_name == "**")
+                {
+                    return get_card_by_id(client, short_link);
+                }
+            }
+        }
+    }
+
+    if board_name == "**" {
+        return find_in_boards(client, &params);
+    }
+
+    let mut board = get_board(client, board_name, &params)?;
+    retrieve_for_lookup(client, &mut board, &params)?;
+
+    find_in_board(board, &params)
+}
+
+/// Retrieves what is needed to look up the list and card of the params within a
+/// board. Unless a card is looked up, only the (cacheable) structure of the board
+/// is retrieved rather than all of its cards.
+fn retrieve_for_lookup(
+    client: &TrelloClient,
+    board: &mut Board,
+    params: &TrelloParams,
+) -> Result<(), TrelloError> {
+    match params.card_name {
+        Some(_) => board.retrieve_nested(client),
+        None => board.retrieve_structure(client),
+    }
+}
+
+/// Finds the list and card matching the params in every open board, for the
+/// '**' board wildcard. Exactly one board must contain a match.
+fn find_in_boards(
+    client: &TrelloClient,
+    params: &TrelloParams,
+) -> Result<TrelloResult, Box<dyn std::error::Error>> {
+    if params.list_name.is_none() {
+        return Err(Box::new(FindError::WildCard(
+            "List or card name must be specified with board '**' wildcard".to_string(),
+        )));
+    }
+
+    let mut results = vec![];
+    for mut board in Board::get_all(client)? {
+        retrieve_for_lookup(client, &mut board, params)?;
+        match find_in_board(board, params) {
+            Ok(result) => results.push(result),
+            Err(e) if matches!(e.downcast_ref(), Some(FindError::NotFound(_))) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let (object_type, name) = match params.card_name {
+        Some(card_name) => ("Card", card_name),
+        None => ("List", params.list_name.unwrap_or_default()),
+    };
+    match results.len().cmp(&1) {
+        Ordering::Equal => Ok(results.remove(0)),
+        Ordering::Greater => Err(Box::new(FindError::Multiple(format!(
+            "More than one {} found. Specify a more precise filter than '{}' (Found on boards {})",
+            object_type,
+            name,
+            results
+                .iter()
+                .flat_map(|r| &r.board)
+                .map(|b| format!("'{}'", b.name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )))),
+        Ordering::Less => Err(Box::new(FindError::NotFound(format!(
+            "{} not found on any board. Specify a more precise filter than '{}'",
+            object_type, name
+        )))),
+    }
+}
+
+/// Finds the list and card matching the params within a board with its nested
+/// lists and cards retrieved
+pub fn find_in_board(
+    board: Board,
+    params: &TrelloParams,
+) -> Result<TrelloResult, Box<dyn std::error::Error>> {
+    let lists = board
+        .lists
+        .as_ref()
+        .ok_or("Board lists not populated after retrieve_nested")?;
+
+    match (params.list_name, params.card_name) {
+        (Some("-" | "**"), Some(card_name)) => {
             let cards = lists
-                .into_iter()
-                .flat_map(|l| l.cards.unwrap_or_default())
+                .iter()
+                .flat_map(|l| l.cards.clone().unwrap_or_default())
                 .collect::<Vec<Card>>();
-            let card = get_object_by_name(&cards, card_name, params.ignore_case)?;
+            let card = get_card(&cards, card_name, params)?.clone();
+            let list = lists
+                .iter()
+                .find(|l| l.cards.iter().flatten().any(|c| c.id == card.id))
+                .cloned();
 
-            return Ok(TrelloResult {
-                board: Some(board_out),
-                list: None,
-                card: Some(card.clone()),
-            });
-        } else {
-            Err(Box::new(FindError::WildCard(
-                "Card name must be specified with list '-' wildcard".to_string(),
-            )))
-        }
-    } else if let Some(list_name) = params.list_name {
-        let lists = board
-            .lists
-            .as_ref()
-            .ok_or("Board lists not populated after retrieve_nested")?;
-        let list = get_object_by_name(lists, list_name, params.ignore_case)?.clone();
-
-        if let Some(card_name) = params.card_name {
-            let cards = list
-                .cards
-                .as_ref()
-                .ok_or("List cards not populated after retrieve_nested")?;
-
-            let card = get_object_by_name(cards, card_name, params.ignore_case)?.clone();
-            return Ok(TrelloResult {
+            Ok(TrelloResult {
                 board: Some(board),
-                list: Some(list),
+                list,
                 card: Some(card),
-            });
-        } else {
-            return Ok(TrelloResult {
+            })
+        }
+        (Some(wildcard @ ("-" | "**")), None) => Err(Box::new(FindError::WildCard(format!(
+            "Card name must be specified with list '{}' wildcard",
+            wildcard
+        )))),
+        (Some(list_name), card_name) => {
+            let list = get_list(lists, list_name, params)?.clone();
+            let card = match card_name {
+                Some(card_name) => {
+                    let cards = list
+                        .cards
+                        .as_ref()
+                        .ok_or("List cards not populated after retrieve_nested")?;
+                    Some(get_card(cards, card_name, params)?.clone())
+                }
+                None => None,
+            };
+
+            Ok(TrelloResult {
                 board: Some(board),
                 list: Some(list),
-                card: None,
-            });
+                card,
+            })
         }
-    } else {
-        return Ok(TrelloResult {
+        (None, _) => Ok(TrelloResult {
             board: Some(board),
             list: None,
             card: None,
-        });
+        }),
     }
 }
@@ -7,19 +7,65 @@ extern crate clap;
 #[macro_use]
 extern crate log;
 
+#[cfg(test)]
+mod test_dates;
+#[cfg(test)]
+mod test_files;
 #[cfg(test)]
 mod test_find;
+#[cfg(test)]
+mod test_output;
+#[cfg(test)]
+mod test_paths;
 
 mod cli;
+mod dates;
+mod files;
 mod find;
+mod output;
+mod paths;
 mod subcommands;
 
+use clap::ArgMatches;
 use colored::*;
 use simplelog::{CombinedLogger, Config, LevelFilter, TermLogger, TerminalMode};
 use std::env;
 use std::error::Error;
+use std::path::Path;
 use std::process;
-use trello::{ClientConfig, TrelloClient};
+use trello::{ClientConfig, OfflineTrello, TrelloClient, TrelloError};
+
+/// Name of the subcommand if it modifies objects with the given arguments. These are
+/// refused up front when reading from an export, rather than failing at their first
+/// change after prompting or opening an editor.
+fn mutating_subcommand(matches: &ArgMatches) -> Option<&str> {
+    let (name, args) = matches.subcommand()?;
+    let any_present = |flags: &[&str]| flags.iter().any(|f| args.is_present(f));
+
+    let mutating = match name {
+        "move" | "attach" | "label" | "assign" | "open" | "close" | "create" | "import" => true,
+        "comment" => any_present(&["add", "message", "stdin", "edit", "delete"]),
+        "due" => any_present(&["date", "clear", "complete", "incomplete"]),
+        "field" => any_present(&["value", "clear"]),
+        "labels" => any_present(&["create", "rename", "color", "delete"]),
+        "attachments" => any_present(&["delete"]),
+        _ => false,
+    };
+    mutating.then_some(name)
+}
+
+/// Exit codes allow scripts to react to specific kinds of Trello failures.
+/// All other errors exit with code 2.
+fn exit_code(error: &(dyn Error + 'static)) -> i32 {
+    match error.downcast_ref::<TrelloError>() {
+        Some(TrelloError::Unauthorized(_)) => 3,
+        Some(TrelloError::NotFound(_)) => 4,
+        Some(TrelloError::RateLimited { .. }) => 5,
+        Some(TrelloError::Api { .. }) => 6,
+        Some(TrelloError::Offline(_)) => 7,
+        _ => 2,
+    }
+}
 
 fn main() {
     if let Err(error) = start() {
@@ -31,20 +77,35 @@ fn main() {
             eprintln!("{}", error);
             debug!("{:?}", error);
         }
-        process::exit(2);
+        process::exit(exit_code(error.as_ref()));
     }
 }
 
 fn start() -> Result<(), Box<dyn Error>> {
-    let matches = clap_app!(tro =>
+    let app = clap_app!(tro =>
         (version: env!("CARGO_PKG_VERSION"))
         (about: env!("CARGO_PKG_DESCRIPTION"))
         (@arg log_level: -l --("log-level") +takes_value possible_values(&["TRACE", "DEBUG", "INFO", "WARN", "ERROR"]) default_value[ERROR] "Specify the log level")
+        (@arg from_export: --("from-export") +takes_value +global "Read from a board exported as JSON from Trello instead of the Trello API")
+        (@arg fuzzy: --fuzzy +global "Match names fuzzily (as fzf does) rather than as regular expressions")
+        (@arg profile: --profile +takes_value +global "Profile of the configuration to use. Defaults to TRO_PROFILE or the default profile")
+        (@arg refresh: --refresh +global "Ignore cached boards, lists and labels and fetch them from Trello")
+        (@arg output: -o --output +takes_value +global possible_values(&["text", "json", "jsonl"]) default_value[text] "Output format of the affected objects")
         (@subcommand version =>
             (about: "Print tro version")
         )
         (@subcommand setup =>
-            (about: "Setup tro")
+            (about: "Setup tro. Use --profile to add further profiles")
+            (@arg manual: --manual "Enter a token generated by hand instead of authorizing tro in a browser")
+            (@arg no_browser: --("no-browser") "Print the authorization url without opening a browser")
+            (@arg port: --port +takes_value "Port on which to listen for the authorization redirect. Chosen at random by default")
+            (@arg scope: --scope +takes_value "Permissions to request for the token. Defaults to read,write")
+            (@arg expiration: --expiration +takes_value possible_values(&["1hour", "1day", "30days", "never"]) "Lifetime of the token. Defaults to never")
+            (@arg app_name: --("app-name") +takes_value "Application name shown when authorizing tro. Defaults to tro")
+        )
+        (@subcommand profiles =>
+            (about: "List profiles or change the default profile")
+            (@arg switch: -s --switch +takes_value "Make the given profile the default profile")
         )
         (@subcommand me =>
             (about: "Show currently logged in user")
@@ -65,7 +126,7 @@ fn start() -> Result<(), Box<dyn Error>> {
             (@arg board_name: +required "Board Name")
             (@arg list_name: +required "List Name")
             (@arg card_name: +required "Card Name")
-            (@arg new_list_name: +required "New List Name")
+            (@arg new_list_name: +required "New List Name, or board/list to move the card to another board")
         )
         (@subcommand search =>
             (about: "Search Trello cards")
@@ -79,19 +140,25 @@ https://help.trello.com/article/808-searching-for-cards-all-boards")
             (@arg interactive: -i --interactive "Enables interactive mode")
         )
         (@subcommand attach =>
-            (about: "Attach a file to a card")
+            (about: "Attach files or links to a card")
             (@arg board_name: +required "Board name to retrieve")
             (@arg list_name: +required "List name to retrieve")
             (@arg card_name: +required "Card name to retrieve")
             (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
-            (@arg path: +required "Path of file to upload")
+            (@arg path: +required +multiple "Paths of files to upload, urls to attach as links or '-' to read from stdin")
+            (@arg name: -n --name +takes_value "Name of the attachment (required when reading from stdin)")
         )
         (@subcommand attachments =>
-            (about: "View attachments")
+            (about: "View, download or delete attachments")
             (@arg board_name: +required "Board name to retrieve")
             (@arg list_name: +required "List name to retrieve")
             (@arg card_name: +required "Card name to retrieve")
             (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+            (@group action =>
+                (@arg download: -d --download +takes_value min_values(0) value_name("DIR") conflicts_with("delete") "Download attachments to a directory (defaults to the current directory)")
+                (@arg delete: --delete +takes_value min_values(0) value_name("NAME") "Delete the attachment with the given name")
+            )
+            (@arg interactive: -i --interactive requires("action") "Select the attachments to download or delete")
         )
         (@subcommand label =>
             (about: "Apply or remove a label on a card")
@@ -103,6 +170,64 @@ https://help.trello.com/article/808-searching-for-cards-all-boards")
             (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
             (@arg interactive: -i --interactive "Enables interactive mode")
         )
+        (@subcommand labels =>
+            (about: "View, create, rename, recolour or delete the labels of a board")
+            (@arg board_name: +required "Board name to retrieve")
+            (@arg label_name: !required "Label name to modify")
+            (@arg create: --create +takes_value conflicts_with[label_name] "Create a label with the given name")
+            (@arg rename: --rename +takes_value requires("label_name") "Rename the label")
+            (@arg color: --color +takes_value "Color of the created label, or new color of the label. Use 'none' for no color")
+            (@arg delete: -d --delete requires("label_name") conflicts_with[rename color] "Delete the label from the board and all of its cards")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+        )
+        (@subcommand assign =>
+            (about: "Assign or unassign members on a card")
+            (@arg board_name: +required "Board name to retrieve")
+            (@arg list_name: +required "List name to retrieve")
+            (@arg card_name: +required "Card name to retrieve")
+            (@arg member_name: required_unless("interactive") +multiple "Username of the member to assign")
+            (@arg delete: -d --delete conflicts_with("interactive") "Unassign specified members")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+            (@arg interactive: -i --interactive "Enables interactive mode")
+        )
+        (@subcommand due =>
+            (about: "Show, set or clear the due date of a card")
+            (long_about: "
+Shows, sets or clears the due date of a card.
+Dates are interpreted in the local timezone and can be absolute (e.g. '2020-06-28 17:00')
+or relative (e.g. 'tomorrow', '+3d', 'next friday 17:00').")
+            (@arg board_name: +required "Board name to retrieve")
+            (@arg list_name: +required "List name to retrieve")
+            (@arg card_name: +required "Card name to retrieve")
+            (@arg date: conflicts_with("clear") "Due date to set")
+            (@arg clear: --clear "Clear the due date")
+            (@arg complete: --complete conflicts_with("incomplete") "Mark the due date as complete")
+            (@arg incomplete: --incomplete "Mark the due date as incomplete")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+        )
+        (@subcommand field =>
+            (about: "View or set custom field values on a card")
+            (@arg board_name: +required "Board name to retrieve")
+            (@arg list_name: +required "List name to retrieve")
+            (@arg card_name: +required "Card name to retrieve")
+            (@arg field_name: !required "Name of the custom field. Displays all custom fields if not specified")
+            (@arg value: !required "Value to set on the custom field")
+            (@arg clear: --clear requires("field_name") conflicts_with("value") "Clear the value of the custom field")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+        )
+        (@subcommand comment =>
+            (about: "View, post, edit or delete comments on a card")
+            (@arg board_name: +required "Board name to retrieve")
+            (@arg list_name: +required "List name to retrieve")
+            (@arg card_name: +required "Card name to retrieve")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+            (@arg add: -a --add "Post a new comment using your editor")
+            (@arg message: -m --message +takes_value conflicts_with("stdin") "Specify the comment text without opening an editor")
+            (@arg stdin: --stdin "Read the comment text from stdin")
+            (@arg edit: -e --edit conflicts_with[add delete] "Edit one of your comments")
+            (@arg delete: -d --delete conflicts_with[add message stdin] "Delete one of your comments")
+            (@arg id: --id +takes_value "Id of the comment to edit or delete. Prompts for a comment if not specified")
+        )
         (@subcommand url =>
             (about: "Display object url")
             (@arg board_name: !required "Board Name to retrieve")
@@ -123,6 +248,17 @@ https://help.trello.com/article/808-searching-for-cards-all-boards")
             (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
             (@arg interactive: -i --interactive "Enables interactive mode")
         )
+        (@subcommand export =>
+            (about: "Export a board to a JSON snapshot")
+            (@arg board_name: +required "Board name to export")
+            (@arg case_sensitive: -c --("case-sensitive") "Use case sensitive names when searching")
+            (@arg file: -f --file +takes_value "Write the export to a file instead of stdout")
+        )
+        (@subcommand import =>
+            (about: "Recreate a board from a JSON snapshot created with export")
+            (@arg file: +required "Path of the export to import. Use - to read from stdin")
+            (@arg name: -n --name +takes_value "Name of the new board. Defaults to the name of the exported board")
+        )
         (@subcommand create =>
             (about: "Create objects")
             (@arg board_name: !required "Board Name to retrieve")
@@ -131,8 +267,13 @@ https://help.trello.com/article/808-searching-for-cards-all-boards")
             (@arg show: --show -s "Show the item once created")
             (@arg label: --label -l +takes_value +multiple "Apply labels to card on creation")
             (@arg name: +takes_value --name -n "Specify the name of the object being created without a prompt")
+            (@arg template: -t --template +takes_value conflicts_with[board_name list_name name label show] "Create a board from a TOML template. Only objects missing from an existing board are created")
+            (@arg no_default_lists: --("no-default-lists") "Do not add the default lists (To Do, Doing, Done) to new boards")
         )
-    ).arg_required_else_help(true).global_setting(clap::AppSettings::ColoredHelp).get_matches();
+    ).arg_required_else_help(true).global_setting(clap::AppSettings::ColoredHelp);
+
+    let args = paths::expand_paths(&app, env::args_os().collect());
+    let matches = app.get_matches_from(args);
 
     let log_level = match matches
         .value_of("log_level")
@@ -165,42 +306,101 @@ https://help.trello.com/article/808-searching-for-cards-all-boards")
         return Ok(());
     }
 
-    let config = match ClientConfig::load_config() {
-        Ok(client) => client,
-        Err(_) => {
-            println!("Unable to load client configuration");
-            println!("Please run {}", "tro setup".green());
-            return Ok(());
+    if let Some(matches) = matches.subcommand_matches("profiles") {
+        subcommands::profiles_subcommand(matches)?;
+        return Ok(());
+    }
+
+    let client = match matches.value_of("from_export") {
+        Some(path) => {
+            if let Some(subcommand) = mutating_subcommand(&matches) {
+                return Err(Box::new(TrelloError::Offline(format!(
+                    "The {} subcommand cannot modify objects when used with --from-export",
+                    subcommand
+                ))));
+            }
+            OfflineTrello::load(Path::new(path))?.client()
+        }
+        None => {
+            let profile = matches.value_of("profile");
+            let mut config = match ClientConfig::load_config(profile) {
+                Ok(client) => client,
+                Err(e) => {
+                    debug!("{}", e);
+                    println!("Unable to load client configuration");
+                    match profile {
+                        Some(profile) => println!(
+                            "Please run {}",
+                            format!("tro setup --profile {}", profile).green()
+                        ),
+                        None => println!("Please run {}", "tro setup".green()),
+                    }
+                    return Ok(());
+                }
+            };
+            if let Some(path) = ClientConfig::world_readable_config() {
+                eprintln!(
+                    "{} {} is readable by other users. Run {} to restrict it",
+                    "Warning:".yellow(),
+                    path.display(),
+                    format!("chmod 600 {}", path.display()).green()
+                );
+            }
+            if let Some(cache) = config.cache.as_mut() {
+                cache.refresh = matches.is_present("refresh");
+            }
+            TrelloClient::new(config)
         }
     };
-    let client = TrelloClient::new(config);
 
     debug!("Loaded configuration: {:?}", client);
 
+    let result = run_subcommand(&client, &matches);
+    // logged before any error is returned, so that failed commands are covered too
+    debug!("Made {} requests to Trello", client.request_count());
+    result
+}
+
+fn run_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     if matches.subcommand_matches("version").is_some() {
         eprintln!(env!("CARGO_PKG_VERSION"));
     } else if let Some(matches) = matches.subcommand_matches("me") {
-        subcommands::me_subcommand(&client, matches)?;
+        subcommands::me_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("show") {
-        subcommands::show_subcommand(&client, matches)?;
+        subcommands::show_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("move") {
-        subcommands::move_subcommand(&client, matches)?;
+        subcommands::move_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("search") {
-        subcommands::search_subcommand(&client, matches)?;
+        subcommands::search_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("attach") {
-        subcommands::attach_subcommand(&client, matches)?;
+        subcommands::attach_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("attachments") {
-        subcommands::attachments_subcommand(&client, matches)?;
+        subcommands::attachments_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("label") {
-        subcommands::label_subcommand(&client, matches)?;
+        subcommands::label_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("labels") {
+        subcommands::labels_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("assign") {
+        subcommands::assign_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("due") {
+        subcommands::due_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("field") {
+        subcommands::field_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("comment") {
+        subcommands::comment_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("url") {
-        subcommands::url_subcommand(&client, matches)?;
+        subcommands::url_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("close") {
-        subcommands::close_subcommand(&client, matches)?;
+        subcommands::close_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("open") {
-        subcommands::open_subcommand(&client, matches)?;
+        subcommands::open_subcommand(client, matches)?;
     } else if let Some(matches) = matches.subcommand_matches("create") {
-        subcommands::create_subcommand(&client, matches)?;
+        subcommands::create_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("export") {
+        subcommands::export_subcommand(client, matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("import") {
+        subcommands::import_subcommand(client, matches)?;
     }
+
     Ok(())
 }
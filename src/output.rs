@@ -0,0 +1,64 @@
+use clap::ArgMatches;
+use serde::Serialize;
+use std::error::Error;
+use std::io::{self, Write};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Format in which subcommands display the objects they retrieve or modify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable (and possibly coloured) text
+    Text,
+    /// A single pretty printed JSON document
+    Json,
+    /// One compact JSON object per line
+    Jsonl,
+}
+
+impl OutputFormat {
+    pub fn from_matches(matches: &ArgMatches) -> OutputFormat {
+        match matches.value_of("output") {
+            Some("json") => OutputFormat::Json,
+            Some("jsonl") => OutputFormat::Jsonl,
+            _ => OutputFormat::Text,
+        }
+    }
+
+    pub fn is_text(self) -> bool {
+        self == OutputFormat::Text
+    }
+
+    /// Writes a single object in the output format. Nothing is written for Text,
+    /// which subcommands render themselves.
+    pub fn write_object<T: Serialize, W: Write>(self, writer: &mut W, value: &T) -> Result<()> {
+        match self {
+            OutputFormat::Text => {}
+            OutputFormat::Json => writeln!(writer, "{}", serde_json::to_string_pretty(value)?)?,
+            OutputFormat::Jsonl => writeln!(writer, "{}", serde_json::to_string(value)?)?,
+        }
+        Ok(())
+    }
+
+    /// Writes a collection of objects in the output format: a JSON array for Json
+    /// or one line per object for Jsonl.
+    pub fn write_objects<T: Serialize, W: Write>(self, writer: &mut W, values: &[T]) -> Result<()> {
+        match self {
+            OutputFormat::Jsonl => {
+                for value in values {
+                    self.write_object(writer, value)?;
+                }
+                Ok(())
+            }
+            _ => self.write_object(writer, &values),
+        }
+    }
+
+    pub fn print_object<T: Serialize>(self, value: &T) -> Result<()> {
+        self.write_object(&mut io::stdout(), value)
+    }
+
+    pub fn print_objects<T: Serialize>(self, values: &[T]) -> Result<()> {
+        self.write_objects(&mut io::stdout(), values)
+    }
+}
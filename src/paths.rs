@@ -0,0 +1,147 @@
+use crate::find::Reference;
+use clap::App;
+use std::ffi::OsString;
+
+/// Object arguments of subcommands, in the order in which they are given
+const OBJECT_ARGS: &[&str] = &["board_name", "list_name", "card_name"];
+
+/// Splits a `board/list/card` path into its components. Slashes within names are
+/// escaped as `\/` and backslashes as `\\`. Other escapes are left untouched so
+/// that they keep their meaning within regex patterns.
+/// Returns None when the value is not a path, including Trello urls.
+pub fn split_path(value: &str) -> Option<Vec<String>> {
+    if !matches!(Reference::parse(value), Reference::Name(_)) {
+        return None;
+    }
+
+    let mut components = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(next @ ('/' | '\\'))) => {
+                chars.next();
+                components.last_mut().unwrap().push(next);
+            }
+            ('/', _) => components.push(String::new()),
+            (c, _) => components.last_mut().unwrap().push(c),
+        }
+    }
+
+    match components.len() {
+        1 => None,
+        _ => Some(components),
+    }
+}
+
+/// Expands a `board/list/card` path given in place of the board name of a
+/// subcommand into separate board, list and card arguments, so that the
+/// arguments following it are parsed as usual.
+///
+/// The arguments are left alone when they are also valid as separate patterns,
+/// i.e. when a list or card pattern follows the path, or when enough arguments
+/// follow it to fill the list and card slots along with the other required
+/// positionals. `tro attach "Q1/Q2" today rust file.txt` therefore still refers
+/// to the board "Q1/Q2".
+pub fn expand_paths<'a>(app: &App<'a>, args: Vec<OsString>) -> Vec<OsString> {
+    let mut subcommand = None;
+    let mut index = 1;
+    while index < args.len() {
+        let arg = args[index].to_string_lossy();
+        let command = subcommand.unwrap_or(app);
+
+        if arg == "--" {
+            index += 1;
+            break;
+        } else if arg.starts_with('-') && arg.len() > 1 {
+            index += match takes_value(app, command, &arg) {
+                true => 2,
+                false => 1,
+            };
+        } else if subcommand.is_none() {
+            match app.find_subcommand(arg.as_ref()) {
+                Some(command) => subcommand = Some(command),
+                None => return args,
+            }
+            index += 1;
+        } else {
+            break;
+        }
+    }
+
+    let command = match subcommand {
+        Some(command) => command,
+        None => return args,
+    };
+    let slots = command
+        .get_positionals()
+        .filter(|a| OBJECT_ARGS.contains(&a.get_id()))
+        .count();
+
+    let required = command
+        .get_positionals()
+        .filter(|a| !OBJECT_ARGS.contains(&a.get_id()) && a.is_required_set())
+        .count();
+    let remaining = count_positionals(app, command, args.get(index + 1..).unwrap_or_default());
+
+    let components = match args
+        .get(index)
+        .and_then(|a| a.to_str())
+        .and_then(split_path)
+    {
+        Some(components) if slots > 1 && components.len() <= slots => components,
+        _ => return args,
+    };
+    // positionals following a partial path would fill the list or card slots,
+    // and enough of them make up separate patterns
+    let unambiguous =
+        remaining == 0 || (components.len() == slots && remaining < slots - 1 + required);
+    if !unambiguous {
+        return args;
+    }
+
+    args[..index]
+        .iter()
+        .cloned()
+        .chain(components.into_iter().map(OsString::from))
+        .chain(args[index + 1..].iter().cloned())
+        .collect()
+}
+
+/// Number of positional arguments among the given arguments
+fn count_positionals<'a>(app: &App<'a>, command: &App<'a>, args: &[OsString]) -> usize {
+    let mut count = 0;
+    let mut index = 0;
+    while index < args.len() {
+        let arg = args[index].to_string_lossy();
+        if arg == "--" {
+            return count + args.len() - index - 1;
+        } else if arg.starts_with('-') && arg.len() > 1 {
+            index += match takes_value(app, command, &arg) {
+                true => 2,
+                false => 1,
+            };
+        } else {
+            count += 1;
+            index += 1;
+        }
+    }
+    count
+}
+
+/// Whether the given flag is followed by a separate value
+fn takes_value<'a>(app: &App<'a>, command: &App<'a>, flag: &str) -> bool {
+    if flag.contains('=') {
+        return false;
+    }
+
+    let matches = |a: &&clap::Arg| match flag.strip_prefix("--") {
+        Some(long) => a.get_long() == Some(long),
+        None => flag.len() == 2 && a.get_short() == flag.chars().nth(1),
+    };
+
+    command
+        .get_arguments()
+        .chain(app.get_arguments())
+        .find(matches)
+        .is_some_and(|a| a.is_takes_value_set())
+}
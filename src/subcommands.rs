@@ -1,47 +1,98 @@
-use crate::{cli, find};
+use crate::output::OutputFormat;
+use crate::{cli, dates, files, find, paths};
+use chrono::{Local, Utc};
 use clap::ArgMatches;
 use colored::*;
+use serde::Serialize;
 use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use trello::{
-    Attachment, Board, Card, ClientConfig, Label, List, Member, Renderable, SearchOptions,
-    TrelloClient, search,
+    AppliedTemplate, Attachment, AuthorizeOptions, Board, BoardExport, BoardTemplate, Card,
+    ClientConfig, Comment, CustomField, CustomFieldItem, CustomFieldType, CustomFieldValue, Label,
+    LabelColor, List, LoopbackListener, Member, Renderable, SearchOptions, TrelloClient, search,
 };
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
+/// How long setup waits for the user to authorize tro in their browser
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+
 pub fn setup_subcommand(matches: &ArgMatches) -> Result<()> {
     debug!("Running setup subcommand with {:?}", matches);
 
     println!("{}", "Welcome to tro!".green().bold());
     println!();
-    println!(
-        "Please generate a Developer {} and {} from https://trello.com/app-key/",
-        "key".green(),
-        "token".green()
-    );
-    println!("and enter them below");
-    println!();
 
-    let key = cli::get_input("Enter Developer API Key: ")?;
-    let token = cli::get_input("Enter Token: ")?;
+    let (key, token) = match matches.is_present("manual") {
+        true => {
+            println!(
+                "Please generate a Developer {} and {} from https://trello.com/app-key/",
+                "key".green(),
+                "token".green()
+            );
+            println!("and enter them below");
+            println!();
 
-    let config = ClientConfig {
-        host: ClientConfig::default_host(),
-        key,
-        token,
+            let key = cli::get_input("Enter Developer API Key: ")?;
+            let token = cli::get_input("Enter Token: ")?;
+            (key, token)
+        }
+        false => {
+            println!(
+                "Please generate a Developer {} from https://trello.com/app-key/",
+                "key".green()
+            );
+            println!("and enter it below");
+            println!();
+
+            let key = cli::get_input("Enter Developer API Key: ")?;
+            let token = authorize(matches, &key)?;
+            (key, token)
+        }
     };
 
+    let mut profiles = ClientConfig::load_profiles()?;
+    let profile = profiles.resolve(matches.value_of("profile"))?;
+
+    // the other settings of an existing profile, such as its host, are kept
+    let mut config = profiles
+        .profiles
+        .get(&profile)
+        .cloned()
+        .unwrap_or_else(|| ClientConfig::new(&ClientConfig::default_host(), "", ""));
+    config.key = key.clone();
+    config.token = token.clone();
+    config.profile = Some(profile.clone());
+
     let client = TrelloClient::new(config);
 
     println!();
 
     match Member::me(&client) {
         Ok(member) => {
-            client.config.save_config()?;
+            profiles.set_credentials(&profile, &key, &token)?;
+            ClientConfig::save_profiles(&profiles)?;
             println!(
                 "Successfully logged in as {} with tro!",
                 member.username.green()
             );
+            println!("Saved as the {} profile", profile.green());
+
+            for (name, command) in [
+                ("key", &client.config.key_command),
+                ("token", &client.config.token_command),
+            ] {
+                if let Some(command) = command {
+                    println!(
+                        "The {} is read from '{}' and was not saved. Update it there if it changed",
+                        name,
+                        command.green()
+                    );
+                }
+            }
         }
         Err(_) => {
             println!(
@@ -54,14 +105,97 @@ pub fn setup_subcommand(matches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
+/// Lets the user authorize tro on Trello in their browser, and captures the
+/// resulting token with a listener on the loopback interface
+fn authorize(matches: &ArgMatches, key: &str) -> Result<String> {
+    let defaults = AuthorizeOptions::default();
+    let option =
+        |name: &str, default: String| matches.value_of(name).map(String::from).unwrap_or(default);
+    let options = AuthorizeOptions {
+        app_name: option("app_name", defaults.app_name),
+        scope: option("scope", defaults.scope),
+        expiration: option("expiration", defaults.expiration),
+    };
+    let port = matches.value_of("port").unwrap_or("0").parse::<u16>()?;
+
+    let listener = LoopbackListener::bind(port)?;
+    let url = options.url(key, &listener.return_url()?)?;
+
+    println!();
+    println!("Open the following url to authorize tro:");
+    println!("{}", url.as_str().blue());
+    if !matches.is_present("no_browser") && !cli::open_in_browser(url.as_str()) {
+        debug!("Unable to open a browser");
+    }
+    println!();
+    println!("Waiting for authorization...");
+
+    Ok(listener.wait_for_token(AUTHORIZE_TIMEOUT)?)
+}
+
+/// A profile of the configuration file, as listed by the profiles subcommand
+#[derive(Serialize)]
+struct ProfileSummary<'a> {
+    name: &'a str,
+    host: &'a str,
+    active: bool,
+}
+
+pub fn profiles_subcommand(matches: &ArgMatches) -> Result<()> {
+    debug!("Running profiles subcommand with {:?}", matches);
+
+    let output = OutputFormat::from_matches(matches);
+    let mut profiles = ClientConfig::load_profiles()?;
+
+    if let Some(name) = matches.value_of("switch") {
+        profiles.set_default(name)?;
+        ClientConfig::save_profiles(&profiles)?;
+
+        eprintln!("Default profile is now {}", name.green());
+        return Ok(());
+    }
+
+    if profiles.profiles.is_empty() {
+        eprintln!("No profiles found. Please run {}", "tro setup".green());
+        return Ok(());
+    }
+
+    let active = profiles.resolve(matches.value_of("profile"))?;
+    let summaries = profiles
+        .profiles
+        .iter()
+        .map(|(name, config)| ProfileSummary {
+            name,
+            host: &config.host,
+            active: *name == active,
+        })
+        .collect::<Vec<ProfileSummary>>();
+
+    if !output.is_text() {
+        output.print_objects(&summaries)?;
+        return Ok(());
+    }
+    for summary in summaries {
+        match summary.active {
+            true => println!("* {}", summary.name.green()),
+            false => println!("  {}", summary.name),
+        }
+    }
+
+    Ok(())
+}
+
 pub fn me_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
     debug!("Running me subcommand with {:?}", matches);
 
     let detailed = matches.is_present("detailed");
+    let output = OutputFormat::from_matches(matches);
 
     let member = Member::me(client)?;
 
-    if detailed {
+    if !output.is_text() {
+        output.print_object(&member)?;
+    } else if detailed {
         println!("username: {}", member.username);
         println!("full name: {}", member.full_name);
         println!("id: {}", member.id);
@@ -78,12 +212,17 @@ pub fn show_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()
     let label_filter = matches.value_of("label_filter");
     let interactive = matches.is_present("interactive");
     let headers = !matches.is_present("no_headers");
+    let output = OutputFormat::from_matches(matches);
 
     let params = find::get_trello_params(matches);
     debug!("Trello Params: {:?}", params);
 
-    let result = find::get_trello_object(client, &params)?;
+    let mut result = find::get_trello_object(client, &params)?;
     trace!("result: {:?}", result);
+    // lists and boards are shown along with their cards
+    if result.card.is_none() && !interactive {
+        result.retrieve_cards(client)?;
+    }
 
     if interactive {
         if result.card.is_some() {
@@ -92,46 +231,76 @@ pub fn show_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()
             let cards = Card::get_all(client, &list.id)?;
 
             if let Some(index) = cli::select_trello_object(&cards)? {
-                cli::edit_card(client, &cards[index])?;
+                if output.is_text() {
+                    cli::edit_card(client, &cards[index])?;
+                } else {
+                    output.print_object(&cards[index])?;
+                }
             }
         } else if let Some(board) = result.board {
             let lists = List::get_all(client, &board.id, true)?;
 
             if let Some(index) = cli::select_trello_object(&lists)? {
                 // TODO: Allow label filtering
-                println!("{}", &lists[index].render(headers));
+                if output.is_text() {
+                    println!("{}", &lists[index].render(headers));
+                } else {
+                    output.print_object(&lists[index])?;
+                }
             }
         } else {
             let mut boards = Board::get_all(client)?;
 
             if let Some(index) = cli::select_trello_object(&boards)? {
                 boards[index].retrieve_nested(client)?;
-                println!("{}", &boards[index].render(headers));
+                if output.is_text() {
+                    println!("{}", &boards[index].render(headers));
+                } else {
+                    output.print_object(&boards[index])?;
+                }
             }
         }
     } else if let Some(card) = result.card {
-        cli::edit_card(client, &card)?;
+        if output.is_text() {
+            cli::edit_card(client, &card)?;
+        } else {
+            output.print_object(&card)?;
+        }
     } else if let Some(list) = result.list {
         let list = match label_filter {
             Some(label_filter) => list.filter(label_filter),
             None => list,
         };
-        println!("{}", list.render(headers));
+        if output.is_text() {
+            println!("{}", list.render(headers));
+        } else {
+            output.print_object(&list)?;
+        }
     } else if let Some(board) = result.board {
         debug!("Board pattern detected");
         let board = match label_filter {
             Some(label_filter) => board.filter(label_filter),
             None => board,
         };
-        println!("{}", board.render(headers));
+        if output.is_text() {
+            println!("{}", board.render(headers));
+        } else {
+            output.print_object(&board)?;
+        }
     } else {
+        let boards = Board::get_all(client)?;
+
+        if !output.is_text() {
+            output.print_objects(&boards)?;
+            return Ok(());
+        }
+
         if headers {
             println!("Open Boards");
             println!("===========");
             println!();
         }
 
-        let boards = Board::get_all(client)?;
         for b in boards {
             println!("* {}", b.name);
         }
@@ -140,9 +309,26 @@ pub fn show_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()
     Ok(())
 }
 
+/// Structured output of the move subcommand
+#[derive(Serialize)]
+struct MovedCard<'a> {
+    card: &'a Card,
+    from: List,
+    to: List,
+}
+
+/// Copy of a list without its nested cards, for structured output
+fn without_cards(list: &List) -> List {
+    let mut list = list.clone();
+    list.cards = None;
+    list
+}
+
 pub fn move_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
     debug!("Running move subcommand with {:?}", matches);
 
+    let output = OutputFormat::from_matches(matches);
+
     let params = find::get_trello_params(matches);
     let result = find::get_trello_object(client, &params)?;
 
@@ -152,21 +338,56 @@ pub fn move_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()
 
     let board = result.board.ok_or("Unable to retrieve board")?;
     let card = result.card.ok_or("Unable to retrieve card")?;
-    let list = result
-        .list
-        .ok_or("Unable to retrieve list. Wildcards are currently unsupported with move")?;
-
-    let board_lists = board.lists.as_ref().ok_or("Missing target board lists")?;
+    let list = result.list.ok_or("Unable to retrieve list")?;
+
+    let board_lists = board.lists.as_ref().ok_or("Missing board lists")?;
+
+    // the destination is a list of the same board, or a board/list path when no
+    // list matches it, so that list names containing slashes keep working
+    let (new_board, new_list) = match find::get_list(board_lists, new_list_name, &params) {
+        Err(find::FindError::NotFound(message)) => {
+            match paths::split_path(new_list_name).as_deref() {
+                Some([board_name, list_name]) => {
+                    let mut new_board = find::get_board(client, board_name, &params)?;
+                    new_board.retrieve_structure(client)?;
+                    let new_lists = new_board
+                        .lists
+                        .as_ref()
+                        .ok_or("Missing target board lists")?;
+                    let new_list = find::get_list(new_lists, list_name, &params)?.clone();
+                    (Some(new_board), new_list)
+                }
+                Some(_) => return Err("The destination must be a list or a board/list path".into()),
+                None => return Err(find::FindError::NotFound(message).into()),
+            }
+        }
+        result => (None, result?.clone()),
+    };
 
-    let new_list = find::get_object_by_name(board_lists, new_list_name, true)?;
+    let destination = match &new_board {
+        Some(new_board) if new_board.id != card.id_board => {
+            Card::change_board(client, &card.id, &new_board.id, &new_list.id)?;
+            format!("{}/{}", new_board.name, new_list.name)
+        }
+        _ => {
+            Card::change_list(client, &card.id, &new_list.id)?;
+            new_list.name.clone()
+        }
+    };
 
-    Card::change_list(client, &card.id, &new_list.id)?;
+    if !output.is_text() {
+        return output.print_object(&MovedCard {
+            card: &card,
+            from: without_cards(&list),
+            to: without_cards(&new_list),
+        });
+    }
 
     println!(
         "Moved '{}' from '{}' to '{}'",
         card.name.green(),
         list.name.green(),
-        new_list.name.green()
+        destination.green()
     );
 
     Ok(())
@@ -177,22 +398,26 @@ pub fn open_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()
 
     let id = matches.value_of("id").ok_or("Id not provided")?;
     let object_type = matches.value_of("type").ok_or("type not provided")?;
+    let output = OutputFormat::from_matches(matches);
 
     if object_type == "board" {
         debug!("Re-opening board with id {}", &id);
         let board = Board::open(client, id)?;
+        output.print_object(&board)?;
 
         eprintln!("Opened board: {}", &board.name.green());
         eprintln!("id: {}", &board.id);
     } else if object_type == "list" {
         debug!("Re-opening list with id {}", &id);
         let list = List::open(client, id)?;
+        output.print_object(&list)?;
 
         eprintln!("Opened list: {}", &list.name.green());
         eprintln!("id: {}", &list.id);
     } else if object_type == "card" {
         debug!("Re-openning card with id {}", &id);
         let card = Card::open(client, id)?;
+        output.print_object(&card)?;
 
         eprintln!("Opened card: {}", &card.name.green());
         eprintln!("id: {}", &card.id);
@@ -241,6 +466,7 @@ pub fn close_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
     let result = find::get_trello_object(client, &params)?;
 
     let interactive = matches.is_present("interactive");
+    let output = OutputFormat::from_matches(matches);
 
     trace!("result: {:?}", result);
 
@@ -249,29 +475,42 @@ pub fn close_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
             eprintln!("Cannot run interactive mode if you specify a card pattern");
         } else if let Some(list) = result.list {
             let mut cards = Card::get_all(client, &list.id)?;
+            let mut closed = vec![];
 
             for index in cli::multiselect_trello_object(&cards, &[])? {
                 close_card(client, &mut cards[index])?;
+                closed.push(cards[index].clone());
             }
+            output.print_objects(&closed)?;
         } else if let Some(board) = result.board {
             let mut lists = List::get_all(client, &board.id, false)?;
+            let mut closed = vec![];
 
             for index in cli::multiselect_trello_object(&lists, &[])? {
                 close_list(client, &mut lists[index])?;
+                closed.push(lists[index].clone());
             }
+            output.print_objects(&closed)?;
         } else {
             let mut boards = Board::get_all(client)?;
+            let mut closed = vec![];
 
             for index in cli::multiselect_trello_object(&boards, &[])? {
                 close_board(client, &mut boards[index])?;
+                closed.push(boards[index].clone());
             }
+            output.print_objects(&closed)?;
         }
     } else if let Some(mut card) = result.card {
         close_card(client, &mut card)?;
+        output.print_object(&card)?;
     } else if let Some(mut list) = result.list {
         close_list(client, &mut list)?;
+        output.print_object(&without_cards(&list))?;
     } else if let Some(mut board) = result.board {
         close_board(client, &mut board)?;
+        board.lists = None;
+        output.print_object(&board)?;
     }
 
     Ok(())
@@ -280,23 +519,46 @@ pub fn close_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
 pub fn create_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
     debug!("Running create subcommand with {:?}", matches);
 
+    let show = matches.is_present("show");
+    let default_lists = !matches.is_present("no_default_lists");
+    let output = OutputFormat::from_matches(matches);
+
+    if let Some(path) = matches.value_of("template") {
+        let template: BoardTemplate = fs::read_to_string(path)?.parse()?;
+        let applied = template.apply(client, default_lists)?;
+
+        if output.is_text() {
+            print_applied_template(&applied);
+        } else {
+            output.print_object(&applied)?;
+        }
+        return Ok(());
+    }
+
     let params = find::get_trello_params(matches);
     let result = find::get_trello_object(client, &params)?;
 
-    let show = matches.is_present("show");
-
     trace!("result: {:?}", result);
 
     if let Some(list) = result.list {
         let labels_to_apply = if let Some(label_names) = matches.values_of("label") {
             let mut target_labels = vec![];
-            let labels =
-                Label::get_all(client, &result.board.ok_or("Unable to retrieve board")?.id)?;
+            let board = result.board.ok_or("Unable to retrieve board")?;
+            let labels = find::get_labels(client, &board, label_names.clone())?;
 
             for name in label_names {
                 match find::get_object_by_name(&labels, name, true) {
                     // TODO: Cloning below is not great. The entire thing feels quite messy
                     Ok(l) => target_labels.push(l.clone()),
+                    Err(find::FindError::NotFound(e)) => {
+                        match offer_new_label(client, &board, name)? {
+                            Some(label) => target_labels.push(label),
+                            None => {
+                                eprintln!("{}", e);
+                                return Ok(());
+                            }
+                        }
+                    }
                     Err(e) => {
                         eprintln!("{}", e);
                         return Ok(());
@@ -313,15 +575,21 @@ pub fn create_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<
             None => cli::get_input("Card name: ")?,
         };
 
-        let card = Card::create(client, &list.id, &Card::new("", &name, "", None, "", None))?;
+        let mut card = Card::create(client, &list.id, &Card::new("", &name, "", None, "", None))?;
+        let mut applied = vec![];
 
         for label in labels_to_apply {
             match Label::apply(client, &card.id, &label.id) {
-                Ok(_) => eprintln!("Applied {} label", &label.simple_render(),),
+                Ok(_) => {
+                    eprintln!("Applied {} label", &label.simple_render());
+                    applied.push(label);
+                }
                 Err(e) => eprintln!("Unable to apply {} label: {}", &label.simple_render(), e),
             };
         }
+        card.labels = Some(applied);
 
+        output.print_object(&card)?;
         if show {
             cli::edit_card(client, &card)?;
         }
@@ -331,18 +599,118 @@ pub fn create_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<
             None => cli::get_input("List name: ")?,
         };
 
-        List::create(client, &board.id, &name)?;
+        let list = List::create(client, &board.id, &name)?;
+        output.print_object(&list)?;
     } else {
         let name = match matches.value_of("name") {
             Some(n) => String::from(n),
             None => cli::get_input("Board name: ")?,
         };
 
-        Board::create(client, &name)?;
+        let board = Board::create_with_defaults(client, &name, default_lists, true)?;
+        output.print_object(&board)?;
     }
 
     Ok(())
 }
+
+/// Offers to create a label which does not exist on the board yet. Returns None if the
+/// user declines (or is not around to answer).
+fn offer_new_label(client: &TrelloClient, board: &Board, name: &str) -> Result<Option<Label>> {
+    let prompt = format!(
+        "Label '{}' does not exist on '{}'. Create it?",
+        name, board.name
+    );
+    if !cli::confirm(&prompt)? {
+        return Ok(None);
+    }
+
+    let color = cli::select_label_color()?;
+    let label = Label::create(client, &board.id, name, color.as_ref())?;
+    eprintln!("Created {} label", label.simple_render());
+
+    Ok(Some(label))
+}
+
+fn print_applied_template(applied: &AppliedTemplate) {
+    if applied.board_created {
+        eprintln!("Created board {}", applied.board.name.green());
+    }
+    for label in &applied.labels {
+        eprintln!("Created {} label", label.simple_render());
+    }
+    for list in &applied.lists {
+        eprintln!("Created list {}", list.name.green());
+    }
+    for card in &applied.cards {
+        eprintln!("Created card {}", card.name.green());
+    }
+    if applied.is_empty() {
+        eprintln!(
+            "Board {} already matches the template",
+            applied.board.name.green()
+        );
+    }
+    println!("{}", applied.board.url);
+}
+
+pub fn export_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running export subcommand with {:?}", matches);
+
+    let params = find::TrelloParams {
+        board_name: matches.value_of("board_name"),
+        list_name: None,
+        card_name: None,
+        ignore_case: !matches.is_present("case_sensitive"),
+        fuzzy: matches.is_present("fuzzy"),
+        prompt: console::user_attended(),
+    };
+    let result = find::get_trello_object(client, &params)?;
+
+    let board = result.board.ok_or("Unable to retrieve board")?;
+    let export = BoardExport::create(client, &board)?;
+    let contents = serde_json::to_string_pretty(&export)?;
+
+    match matches.value_of("file") {
+        Some(path) => {
+            fs::write(path, contents)?;
+            eprintln!("Exported '{}' to {}", board.name.green(), path);
+        }
+        None => println!("{}", contents),
+    }
+
+    Ok(())
+}
+
+pub fn import_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running import subcommand with {:?}", matches);
+
+    let path = matches.value_of("file").ok_or("Missing file argument")?;
+    let output = OutputFormat::from_matches(matches);
+
+    let contents = match path {
+        "-" => {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+        path => fs::read_to_string(path)?,
+    };
+    let export: BoardExport = serde_json::from_str(&contents)?;
+
+    let name = matches.value_of("name").unwrap_or(&export.board.name);
+    let board = export.import(client, name)?;
+
+    if output.is_text() {
+        eprintln!("Imported board: '{}'", board.name.green());
+        println!("{}", board.url);
+    } else {
+        output.print_object(&board)?;
+    }
+
+    Ok(())
+}
+
 pub fn attachments_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
     debug!("Running attachments subcommand with {:?}", matches);
 
@@ -352,9 +720,105 @@ pub fn attachments_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Re
     let card = result.card.ok_or("Unable to find card")?;
 
     let attachments = Attachment::get_all(client, &card.id)?;
+    let output = OutputFormat::from_matches(matches);
 
-    for att in attachments {
-        println!("{}", &att.url);
+    let interactive = matches.is_present("interactive");
+    let selected = |attachments: &[Attachment]| -> Result<Vec<Attachment>> {
+        Ok(cli::multiselect_trello_object(attachments, &[])?
+            .into_iter()
+            .map(|index| attachments[index].clone())
+            .collect())
+    };
+
+    if matches.is_present("download") {
+        let dir = Path::new(matches.value_of("download").unwrap_or("."));
+        let attachments = match interactive {
+            true => selected(&attachments)?,
+            false => attachments,
+        };
+        return download_attachments(client, &card, &attachments, dir, output);
+    } else if matches.is_present("delete") {
+        let deleted = match (matches.value_of("delete"), interactive) {
+            (Some(name), _) => {
+                vec![find::get_object_by_name(&attachments, name, params.ignore_case)?.clone()]
+            }
+            (None, true) => selected(&attachments)?,
+            (None, false) => {
+                return Err(
+                    "Specify the name of the attachment to delete or use --interactive".into(),
+                );
+            }
+        };
+
+        for attachment in &deleted {
+            Attachment::delete(client, &card.id, &attachment.id)?;
+            eprintln!(
+                "Deleted attachment '{}' from '{}'",
+                attachment.name,
+                card.name.green()
+            );
+        }
+        return output.print_objects(&deleted);
+    }
+
+    if !output.is_text() {
+        return output.print_objects(&attachments);
+    }
+
+    if !attachments.is_empty() {
+        println!("{}", Attachment::render_table(&attachments));
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DownloadedAttachment<'a> {
+    #[serde(flatten)]
+    attachment: &'a Attachment,
+    path: PathBuf,
+}
+
+fn download_attachments(
+    client: &TrelloClient,
+    card: &Card,
+    attachments: &[Attachment],
+    dir: &Path,
+    output: OutputFormat,
+) -> Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let mut downloaded = vec![];
+    for attachment in attachments {
+        let Some(file_name) = attachment.file_name() else {
+            eprintln!("Skipping '{}' as it is a link", attachment.name);
+            continue;
+        };
+
+        // the file name in the url is an escaped version of the attachment name
+        let name = match attachment.name.is_empty() {
+            true => file_name,
+            false => &attachment.name,
+        };
+        let path = files::unique_path(dir, &files::sanitize_filename(name));
+        debug!("Downloading {} to {:?}", attachment.url, path);
+
+        let mut file = fs::File::create(&path)?;
+        if let Err(e) = Attachment::download(client, &card.id, attachment, &mut file) {
+            // do not leave partially written files behind
+            drop(file);
+            fs::remove_file(&path)?;
+            return Err(e.into());
+        }
+
+        if output.is_text() {
+            println!("{}", path.display());
+        }
+        downloaded.push(DownloadedAttachment { attachment, path });
+    }
+
+    if !output.is_text() {
+        return output.print_objects(&downloaded);
     }
 
     Ok(())
@@ -366,13 +830,41 @@ pub fn attach_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<
     let params = find::get_trello_params(matches);
     let result = find::get_trello_object(client, &params)?;
 
-    let path = matches.value_of("path").ok_or("Missing path argument")?;
+    let paths = matches
+        .values_of("path")
+        .ok_or("Missing path argument")?
+        .collect::<Vec<&str>>();
+    let name = matches.value_of("name");
+    let output = OutputFormat::from_matches(matches);
+
+    if name.is_some() && paths.len() > 1 {
+        return Err("--name can only be used when attaching a single file or link".into());
+    }
 
     let card = result.card.ok_or("Unable to find card")?;
 
-    let attachment = Attachment::apply(client, &card.id, path)?;
+    let mut attachments = vec![];
+    for path in paths {
+        let attachment = if path == "-" {
+            let name = name.ok_or("--name is required when reading from stdin")?;
+            Attachment::upload(client, &card.id, name, io::stdin())?
+        } else if path.starts_with("http://") || path.starts_with("https://") {
+            Attachment::link(client, &card.id, path, name.unwrap_or(path))?
+        } else if let Some(name) = name {
+            Attachment::upload(client, &card.id, name, fs::File::open(path)?)?
+        } else {
+            Attachment::apply(client, &card.id, path)?
+        };
+
+        if output.is_text() {
+            println!("{}", attachment.render(true));
+        }
+        attachments.push(attachment);
+    }
 
-    println!("{}", attachment.render(true));
+    if !output.is_text() {
+        return output.print_objects(&attachments);
+    }
 
     Ok(())
 }
@@ -382,15 +874,28 @@ pub fn url_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()>
 
     let params = find::get_trello_params(matches);
     let result = find::get_trello_object(client, &params)?;
+    let output = OutputFormat::from_matches(matches);
 
     if let Some(card) = result.card {
-        println!("{}", card.url);
+        match output.is_text() {
+            true => println!("{}", card.url),
+            false => output.print_object(&card)?,
+        }
     } else if result.list.is_some() {
         // Lists do not have a target url
         // We can display the parent board url instead
-        println!("{}", result.board.ok_or("Unable to retrieve board")?.url);
-    } else if let Some(board) = result.board {
-        println!("{}", board.url);
+        let mut board = result.board.ok_or("Unable to retrieve board")?;
+        board.lists = None;
+        match output.is_text() {
+            true => println!("{}", board.url),
+            false => output.print_object(&board)?,
+        }
+    } else if let Some(mut board) = result.board {
+        board.lists = None;
+        match output.is_text() {
+            true => println!("{}", board.url),
+            false => output.print_object(&board)?,
+        }
     }
     Ok(())
 }
@@ -419,7 +924,7 @@ pub fn search_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<
     let partial = matches.is_present("partial");
     let interactive = matches.is_present("interactive");
 
-    let cards_limit = if let Some(v) = matches.value_of("limit") {
+    let cards_limit = if let Some(v) = matches.value_of("cards_limit") {
         Some(v.parse()?)
     } else {
         None
@@ -434,11 +939,18 @@ pub fn search_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<
     };
 
     let results = search(client, &query, &params)?;
+    let output = OutputFormat::from_matches(matches);
 
     if interactive {
         if let Some(index) = cli::select_trello_object(&results.cards)? {
-            cli::edit_card(client, &results.cards[index])?;
+            if output.is_text() {
+                cli::edit_card(client, &results.cards[index])?;
+            } else {
+                output.print_object(&results.cards[index])?;
+            }
         }
+    } else if !output.is_text() {
+        output.print_objects(&results.cards)?;
     } else if !&results.cards.is_empty() {
         for card in &results.cards {
             println!(
@@ -485,9 +997,12 @@ pub fn label_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
     let interactive = matches.is_present("interactive");
     let delete = matches.is_present("delete");
     let label_names = matches.values_of("label_name");
+    let output = OutputFormat::from_matches(matches);
 
     let card = result.card.ok_or("Unable to find card")?;
     let card_labels = card.labels.as_ref().ok_or("Unable to get card labels")?;
+    // labels of the card once all changes have been made
+    let mut final_labels = card_labels.clone();
 
     if delete {
         let labels = card_labels;
@@ -503,10 +1018,12 @@ pub fn label_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
             };
 
             delete_label(client, &card, label)?;
+            final_labels.retain(|l| l.id != label.id);
         }
     } else {
         let board = result.board.ok_or("Unable to retrieve board")?;
-        let mut labels = Label::get_all(client, &board.id)?;
+        let mut labels =
+            find::get_labels(client, &board, label_names.clone().into_iter().flatten())?;
         labels.sort_by_cached_key(|l| l.name.clone());
 
         if interactive {
@@ -518,12 +1035,14 @@ pub fn label_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
             for label in &selected_labels {
                 if !card_labels.contains(label) {
                     apply_label(client, &card, label)?;
+                    final_labels.push((*label).clone());
                 }
             }
 
             for label in card_labels {
                 if !selected_labels.contains(&label) {
                     delete_label(client, &card, label)?;
+                    final_labels.retain(|l| l.id != label.id);
                 }
             }
         } else {
@@ -543,8 +1062,447 @@ pub fn label_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<(
                 };
 
                 apply_label(client, &card, label)?;
+                final_labels.push(label.clone());
+            }
+        }
+    }
+
+    let mut card = card.clone();
+    card.labels = Some(final_labels);
+    output.print_object(&card)?;
+
+    Ok(())
+}
+
+/// A label along with the number of open cards it is applied to
+#[derive(Serialize)]
+struct LabelUsage<'a> {
+    #[serde(flatten)]
+    label: &'a Label,
+    cards: usize,
+}
+
+pub fn labels_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running labels subcommand with {:?}", matches);
+
+    let params = find::TrelloParams {
+        board_name: matches.value_of("board_name"),
+        list_name: None,
+        card_name: None,
+        ignore_case: !matches.is_present("case_sensitive"),
+        fuzzy: matches.is_present("fuzzy"),
+        prompt: console::user_attended(),
+    };
+    let result = find::get_trello_object(client, &params)?;
+    let mut board = result.board.ok_or("Unable to retrieve board")?;
+
+    let rename = matches.value_of("rename");
+    // "none" removes the color of a label
+    let color = match matches.value_of("color") {
+        Some("none") => Some(None),
+        Some(color) => Some(Some(color.parse::<LabelColor>()?)),
+        None => None,
+    };
+    let output = OutputFormat::from_matches(matches);
+
+    let mut labels = find::get_labels(client, &board, matches.value_of("label_name"))?;
+    labels.sort_by_cached_key(|l| l.name.clone());
+
+    if let Some(name) = matches.value_of("create") {
+        let label = Label::create(client, &board.id, name, color.flatten().as_ref())?;
+
+        eprintln!(
+            "Created {} label on '{}'",
+            label.simple_render(),
+            board.name.green()
+        );
+        output.print_object(&label)?;
+        return Ok(());
+    }
+
+    if let Some(label_name) = matches.value_of("label_name") {
+        let label = find::get_object_by_name(&labels, label_name, params.ignore_case)?;
+
+        if matches.is_present("delete") {
+            Label::delete(client, &label.id)?;
+
+            eprintln!(
+                "Deleted {} label from '{}'",
+                label.simple_render(),
+                board.name.green()
+            );
+            output.print_object(label)?;
+            return Ok(());
+        } else if rename.is_some() || color.is_some() {
+            let mut updated = label.clone();
+            if let Some(name) = rename {
+                updated.name = String::from(name);
+            }
+            if let Some(color) = color {
+                updated.color = color;
+            }
+            let updated = Label::update(client, &updated)?;
+
+            eprintln!(
+                "Updated {} label to {}",
+                label.simple_render(),
+                updated.simple_render()
+            );
+            output.print_object(&updated)?;
+            return Ok(());
+        }
+        labels = vec![label.clone()];
+    }
+
+    board.retrieve_nested(client)?;
+    let cards = board
+        .lists
+        .iter()
+        .flatten()
+        .flat_map(|l| l.cards.iter().flatten())
+        .collect::<Vec<&Card>>();
+    let usages = labels
+        .iter()
+        .map(|label| LabelUsage {
+            label,
+            cards: cards
+                .iter()
+                .filter(|c| c.labels.iter().flatten().any(|l| l.id == label.id))
+                .count(),
+        })
+        .collect::<Vec<LabelUsage>>();
+
+    if !output.is_text() {
+        output.print_objects(&usages)?;
+    } else {
+        for usage in &usages {
+            println!(
+                "{:>4} {} {}",
+                usage.cards,
+                usage.label.simple_render(),
+                format!("id: {}", usage.label.id).green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Determines the text of a comment from the --message or --stdin arguments, falling
+/// back to opening the users editor with the initial text provided.
+fn get_comment_text(matches: &ArgMatches, initial: &str) -> Result<String> {
+    let text = if let Some(message) = matches.value_of("message") {
+        String::from(message)
+    } else if matches.is_present("stdin") {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf.trim_end().to_string()
+    } else {
+        cli::get_text_from_editor(initial)?
+    };
+
+    if text.trim().is_empty() {
+        Err("Comment text cannot be empty".into())
+    } else {
+        Ok(text)
+    }
+}
+
+/// Selects one of the comments written by the current user, either through the
+/// --id argument or by prompting the user to choose one.
+fn select_own_comment(
+    client: &TrelloClient,
+    matches: &ArgMatches,
+    comments: Vec<Comment>,
+) -> Result<Option<Comment>> {
+    let me = Member::me(client)?;
+    let mut comments = comments
+        .into_iter()
+        .filter(|c| c.member_creator.id == me.id)
+        .collect::<Vec<Comment>>();
+
+    if let Some(id) = matches.value_of("id") {
+        let index = comments
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or_else(|| format!("No comment written by you found with id '{}'", id))?;
+        Ok(Some(comments.remove(index)))
+    } else if comments.is_empty() {
+        Err("You have not written any comments on this card".into())
+    } else {
+        Ok(cli::select_trello_object(&comments)?.map(|index| comments.remove(index)))
+    }
+}
+
+pub fn comment_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running comment subcommand with {:?}", matches);
+
+    let params = find::get_trello_params(matches);
+    let result = find::get_trello_object(client, &params)?;
+
+    let card = result.card.ok_or("Unable to find card")?;
+
+    let edit = matches.is_present("edit");
+    let delete = matches.is_present("delete");
+    let add =
+        matches.is_present("add") || matches.is_present("message") || matches.is_present("stdin");
+
+    if edit {
+        let comments = Comment::get_all(client, &card.id)?;
+
+        if let Some(comment) = select_own_comment(client, matches, comments)? {
+            let text = get_comment_text(matches, &comment.data.text)?;
+            Comment::update(client, &card.id, &comment.id, &text)?;
+
+            eprintln!("Updated comment on '{}'", &card.name.green());
+        }
+    } else if delete {
+        let comments = Comment::get_all(client, &card.id)?;
+
+        if let Some(comment) = select_own_comment(client, matches, comments)? {
+            Comment::delete(client, &card.id, &comment.id)?;
+
+            eprintln!("Deleted comment from '{}'", &card.name.green());
+        }
+    } else if add {
+        let text = get_comment_text(matches, "")?;
+        let comment = Comment::create(client, &card.id, &text)?;
+
+        eprintln!("Posted comment on '{}'", &card.name.green());
+        eprintln!("id: {}", &comment.id);
+    } else {
+        let comments = Comment::get_all(client, &card.id)?;
+
+        // Trello returns the most recent comments first
+        let output = comments
+            .iter()
+            .rev()
+            .map(|c| c.render(true))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+fn unassign_member(client: &TrelloClient, card: &Card, member: &Member) -> Result<()> {
+    Card::remove_member(client, &card.id, &member.id)?;
+
+    eprintln!(
+        "Unassigned {} from '{}'",
+        &member.simple_render(),
+        &card.name.green(),
+    );
+
+    Ok(())
+}
+
+fn assign_member(client: &TrelloClient, card: &Card, member: &Member) -> Result<()> {
+    Card::add_member(client, &card.id, &member.id)?;
+
+    eprintln!(
+        "Assigned {} to '{}'",
+        &member.simple_render(),
+        &card.name.green()
+    );
+
+    Ok(())
+}
+
+pub fn assign_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running assign subcommand with {:?}", matches);
+
+    let params = find::get_trello_params(matches);
+    let result = find::get_trello_object(client, &params)?;
+
+    let interactive = matches.is_present("interactive");
+    let delete = matches.is_present("delete");
+    let member_names = matches.values_of("member_name");
+
+    let mut card = result.card.ok_or("Unable to find card")?;
+    let board = result.board.ok_or("Unable to retrieve board")?;
+
+    let mut members = Member::get_all_for_board(client, &board.id)?;
+    members.sort_by_cached_key(|m| m.full_name.clone());
+
+    card.populate_members(&members);
+    let card_members = card.members.as_ref().ok_or("Unable to get card members")?;
+
+    if delete {
+        let member_names = member_names.ok_or("Member names must be specified")?;
+
+        for name in member_names {
+            let member = match find::get_object_by_name(card_members, name, true) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    continue;
+                }
+            };
+
+            unassign_member(client, &card, member)?;
+        }
+    } else if interactive {
+        let selected_members = cli::multiselect_trello_object(&members, card_members)?
+            .into_iter()
+            .map(|i| &members[i])
+            .collect::<Vec<&Member>>();
+
+        for member in &selected_members {
+            if !card_members.contains(member) {
+                assign_member(client, &card, member)?;
+            }
+        }
+
+        for member in card_members {
+            if !selected_members.contains(&member) {
+                unassign_member(client, &card, member)?;
             }
         }
+    } else {
+        let member_names = member_names.ok_or("Member names must be specified")?;
+
+        for name in member_names {
+            let member = match find::get_object_by_name(&members, name, true) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("Member with pattern '{}' not found", name);
+                    debug!("{}", e);
+                    continue;
+                }
+            };
+
+            assign_member(client, &card, member)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn due_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running due subcommand with {:?}", matches);
+
+    let params = find::get_trello_params(matches);
+    let result = find::get_trello_object(client, &params)?;
+
+    let card = result.card.ok_or("Unable to find card")?;
+
+    let clear = matches.is_present("clear");
+    let complete = matches.is_present("complete");
+    let incomplete = matches.is_present("incomplete");
+
+    let mut updated = None;
+
+    if clear {
+        updated = Some(Card::set_due(client, &card.id, None)?);
+        eprintln!("Cleared due date of '{}'", &card.name.green());
+    } else if let Some(date) = matches.value_of("date") {
+        let due = dates::parse_due(date, Local::now())?;
+        updated = Some(Card::set_due(
+            client,
+            &card.id,
+            Some(due.with_timezone(&Utc)),
+        )?);
+        eprintln!(
+            "Set due date of '{}' to {}",
+            &card.name.green(),
+            due.format("%Y-%m-%d %H:%M")
+        );
+    }
+
+    if complete || incomplete {
+        updated = Some(Card::set_due_complete(client, &card.id, complete)?);
+        eprintln!(
+            "Marked due date of '{}' as {}",
+            &card.name.green(),
+            if complete { "complete" } else { "incomplete" }
+        );
+    }
+
+    if updated.is_none() {
+        match card.due {
+            Some(due) => println!("{}", due.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+            None => eprintln!("'{}' has no due date", &card.name.green()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts the value entered by the user into a value suitable for the type of
+/// the given custom field.
+fn parse_custom_field_value(field: &CustomField, value: &str) -> Result<CustomFieldValue> {
+    let value = match field.field_type {
+        CustomFieldType::Text => CustomFieldValue::Text(String::from(value)),
+        CustomFieldType::Number => CustomFieldValue::Number(
+            value
+                .parse()
+                .map_err(|_| format!("'{}' is not a valid number", value))?,
+        ),
+        CustomFieldType::Date => {
+            CustomFieldValue::Date(dates::parse_due(value, Local::now())?.with_timezone(&Utc))
+        }
+        CustomFieldType::Checkbox => match value.to_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" | "checked" => CustomFieldValue::Checkbox(true),
+            "false" | "no" | "n" | "0" | "unchecked" => CustomFieldValue::Checkbox(false),
+            _ => return Err(format!("'{}' is not a valid checkbox value", value).into()),
+        },
+        CustomFieldType::List => {
+            let option = find::get_object_by_name(&field.options, value, true)?;
+            CustomFieldValue::Option(option.id.clone())
+        }
+    };
+    Ok(value)
+}
+
+pub fn field_subcommand(client: &TrelloClient, matches: &ArgMatches) -> Result<()> {
+    debug!("Running field subcommand with {:?}", matches);
+
+    let params = find::get_trello_params(matches);
+    let result = find::get_trello_object(client, &params)?;
+
+    let mut card = result.card.ok_or("Unable to find card")?;
+    card.retrieve_custom_fields(client)?;
+
+    let fields = card
+        .custom_fields
+        .as_ref()
+        .ok_or("Unable to retrieve custom fields")?;
+    let items = card.custom_field_items.as_deref().unwrap_or_default();
+
+    if fields.is_empty() {
+        eprintln!("This board has no custom fields");
+        return Ok(());
+    }
+
+    let field_name = match matches.value_of("field_name") {
+        Some(name) => name,
+        None => {
+            for field in fields {
+                println!(
+                    "{}: {}",
+                    field.name,
+                    field.format_value(items).unwrap_or_default()
+                );
+            }
+            return Ok(());
+        }
+    };
+
+    let field = find::get_object_by_name(fields, field_name, params.ignore_case)?;
+
+    if matches.is_present("clear") {
+        CustomFieldItem::update(client, &card.id, field, None)?;
+
+        eprintln!("Cleared '{}' on '{}'", field.name, &card.name.green());
+    } else if let Some(value) = matches.value_of("value") {
+        let value = parse_custom_field_value(field, value)?;
+        CustomFieldItem::update(client, &card.id, field, Some(&value))?;
+
+        eprintln!("Updated '{}' on '{}'", field.name, &card.name.green());
+    } else if let Some(value) = field.format_value(items) {
+        println!("{}", value);
     }
 
     Ok(())
@@ -0,0 +1,69 @@
+use crate::dates::*;
+use chrono::{DateTime, Local, TimeZone};
+
+// Sunday
+fn now() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2020, 6, 28, 9, 30, 0).unwrap()
+}
+
+fn local(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Local> {
+    Local
+        .with_ymd_and_hms(year, month, day, hour, min, 0)
+        .unwrap()
+}
+
+#[test]
+fn test_absolute_date() {
+    assert_eq!(parse_due("2020-07-01", now()), Ok(local(2020, 7, 1, 12, 0)));
+}
+
+#[test]
+fn test_absolute_date_and_time() {
+    assert_eq!(
+        parse_due("2020-07-01 17:45", now()),
+        Ok(local(2020, 7, 1, 17, 45))
+    );
+}
+
+#[test]
+fn test_tomorrow() {
+    assert_eq!(parse_due("tomorrow", now()), Ok(local(2020, 6, 29, 12, 0)));
+}
+
+#[test]
+fn test_today_with_time() {
+    assert_eq!(
+        parse_due("Today 18:00", now()),
+        Ok(local(2020, 6, 28, 18, 0))
+    );
+}
+
+#[test]
+fn test_time_only() {
+    assert_eq!(parse_due("18:00", now()), Ok(local(2020, 6, 28, 18, 0)));
+}
+
+#[test]
+fn test_offset() {
+    assert_eq!(parse_due("+3d", now()), Ok(local(2020, 7, 1, 9, 30)));
+    assert_eq!(parse_due("-2h", now()), Ok(local(2020, 6, 28, 7, 30)));
+    assert_eq!(parse_due("+1w", now()), Ok(local(2020, 7, 5, 9, 30)));
+}
+
+#[test]
+fn test_next_weekday() {
+    assert_eq!(
+        parse_due("next friday 17:00", now()),
+        Ok(local(2020, 7, 3, 17, 0))
+    );
+    assert_eq!(parse_due("sunday", now()), Ok(local(2020, 7, 5, 12, 0)));
+}
+
+#[test]
+fn test_invalid() {
+    assert!(matches!(
+        parse_due("someday", now()),
+        Err(DateError::Invalid(_))
+    ));
+    assert!(matches!(parse_due("", now()), Err(DateError::Invalid(_))));
+}
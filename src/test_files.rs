@@ -0,0 +1,44 @@
+use crate::files::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_sanitize_filename() {
+    assert_eq!(sanitize_filename("report.pdf"), "report.pdf");
+    assert_eq!(sanitize_filename("../../etc/passwd"), ".._.._etc_passwd");
+    assert_eq!(sanitize_filename("a\\b"), "a_b");
+    assert_eq!(sanitize_filename(".."), "attachment");
+    assert_eq!(sanitize_filename(""), "attachment");
+}
+
+#[test]
+fn test_unique_path() -> std::io::Result<()> {
+    let dir = TempDir::new()?;
+
+    assert_eq!(
+        unique_path(dir.path(), "notes.txt"),
+        dir.path().join("notes.txt")
+    );
+
+    fs::write(dir.path().join("notes.txt"), "")?;
+    assert_eq!(
+        unique_path(dir.path(), "notes.txt"),
+        dir.path().join("notes (1).txt")
+    );
+
+    fs::write(dir.path().join("notes (1).txt"), "")?;
+    assert_eq!(
+        unique_path(dir.path(), "notes.txt"),
+        dir.path().join("notes (2).txt")
+    );
+
+    fs::write(dir.path().join("README"), "")?;
+    assert_eq!(
+        unique_path(dir.path(), "README"),
+        dir.path().join("README (1)")
+    );
+
+    fs::write(dir.path().join(".env"), "")?;
+    assert_eq!(unique_path(dir.path(), ".env"), dir.path().join(".env (1)"));
+    Ok(())
+}
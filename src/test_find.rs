@@ -1,14 +1,30 @@
 use crate::find::*;
 use std::error::Error;
-use trello::{Board, Card, ClientConfig, List, TrelloClient};
+use trello::{Board, CacheConfig, Card, ClientConfig, List, TrelloClient};
 
 type TestResult = Result<(), Box<dyn Error>>;
 
 mod test_get_trello_object {
     use super::*;
-    use mockito;
+    use mockito::Matcher;
     use serde_json::json;
 
+    /// Query of the request which retrieves a board along with its nested resources
+    fn nested_query() -> Matcher {
+        Matcher::AllOf(vec![
+            Matcher::UrlEncoded("lists".into(), "open".into()),
+            Matcher::UrlEncoded("cards".into(), "open".into()),
+            Matcher::UrlEncoded("labels".into(), "all".into()),
+            Matcher::UrlEncoded("members".into(), "all".into()),
+        ])
+    }
+
+    /// Query retrieving the lists and labels of a board without its cards, which
+    /// would otherwise be requested between the lists and the labels
+    fn structure_query() -> Matcher {
+        Matcher::Regex(String::from("&lists=open&list_fields=[^&]*&labels=all&"))
+    }
+
     #[test]
     fn test_empty() -> TestResult {
         let params = TrelloParams {
@@ -16,6 +32,8 @@ mod test_get_trello_object {
             list_name: None,
             card_name: None,
             ignore_case: false,
+            fuzzy: false,
+            prompt: false,
         };
         let config = ClientConfig::new("", "", "");
         let client = TrelloClient::new(config);
@@ -50,46 +68,361 @@ mod test_get_trello_object {
         )
         .create();
 
-        let _m2 = mockito::mock(
+        let _m2 = mockito::mock("GET", "/1/boards/abc-def")
+            .match_query(structure_query())
+            .match_header(
+                "authorization",
+                "OAuth oauth_consumer_key=\"key\", oauth_token=\"token\"",
+            )
+            .with_status(200)
+            .with_body(
+                json!({
+                    "name": "TODO",
+                    "id": "abc-def",
+                    "closed": false,
+                    "url": "",
+                    "lists": [{"name": "Backlog", "id": "bcklg", "closed": false}],
+                    "labels": [],
+                    "members": [],
+                })
+                .to_string(),
+            )
+            .create();
+
+        let params = TrelloParams {
+            board_name: Some("TODO"),
+            list_name: Some("back"),
+            card_name: None,
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
+        };
+        let config = ClientConfig::new(&mockito::server_url(), "token", "key");
+        let client = TrelloClient::new(config);
+
+        let result = get_trello_object(&client, &params)?;
+        let mut board = Board::new(
+            "abc-def",
+            "TODO",
+            Some(vec![List::new("bcklg", "Backlog", None)]),
+            "",
+        );
+        board.labels = Some(vec![]);
+        let expected = TrelloResult {
+            board: Some(board),
+            list: Some(List::new("bcklg", "Backlog", None)),
+            card: None,
+        };
+
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_warm_cache() -> TestResult {
+        let boards = mockito::mock(
             "GET",
-            "/1/boards/abc-def/lists?fields=id%2Cname%2Cclosed&cards=open",
+            "/1/members/me/boards/?filter=open&fields=id%2Cname%2Cclosed%2Curl",
         )
-        .match_header(
-            "authorization",
-            "OAuth oauth_consumer_key=\"key\", oauth_token=\"token\"",
+        .with_status(200)
+        .with_body(
+            json!([{"name": "Cached", "id": "cached-board", "closed": false, "url": ""}])
+                .to_string(),
+        )
+        .expect(1)
+        .create();
+        let structure = mockito::mock("GET", "/1/boards/cached-board")
+            .match_query(structure_query())
+            .with_status(200)
+            .with_body(
+                json!({
+                    "name": "Cached",
+                    "id": "cached-board",
+                    "closed": false,
+                    "url": "",
+                    "lists": [{"name": "Backlog", "id": "cached-list", "closed": false}],
+                    "labels": [],
+                    "members": [],
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let params = TrelloParams {
+            board_name: Some("Cached"),
+            list_name: Some("Backlog"),
+            card_name: None,
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
+        };
+        let dir = tempfile::TempDir::new()?;
+        let client = || {
+            let mut config = ClientConfig::new(&mockito::server_url(), "token", "key");
+            config.cache = Some(CacheConfig {
+                dir: Some(dir.path().to_path_buf()),
+                ..CacheConfig::default()
+            });
+            TrelloClient::new(config)
+        };
+
+        let cold = get_trello_object(&client(), &params)?;
+        // a later command looks the list up without any requests to Trello
+        let warm = get_trello_object(&client(), &params)?;
+
+        assert_eq!(warm, cold);
+        assert_eq!(warm.list.map(|l| l.id), Some(String::from("cached-list")));
+        boards.assert();
+        structure.assert();
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_url() -> TestResult {
+        let _m1 = mockito::mock("GET", "/1/cards/AbCd1234")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "id": "5ed78889acdaf970289ac894",
+                    "name": "Dig up bones",
+                    "desc": "",
+                    "closed": false,
+                    "url": "https://trello.com/c/AbCd1234/7-dig-up-bones",
+                    "labels": [],
+                    "due": null,
+                    "idBoard": "5ed78889acdaf970289ac000",
+                })
+                .to_string(),
+            )
+            .create();
+
+        let _m2 = mockito::mock(
+            "GET",
+            "/1/boards/5ed78889acdaf970289ac000?fields=id%2Cname%2Cclosed%2Curl",
         )
         .with_status(200)
         .with_body(
-            json!([
-                {"name": "Backlog", "id": "bcklg", "closed": false, "cards": [], "url": ""},
-            ])
-            .to_string(),
+            json!({"name": "Dog", "id": "5ed78889acdaf970289ac000", "closed": false, "url": ""})
+                .to_string(),
         )
         .create();
 
+        let _m3 = mockito::mock("GET", "/1/boards/5ed78889acdaf970289ac000")
+            .match_query(nested_query())
+            .with_status(200)
+            .with_body(
+                json!({
+                    "name": "Dog",
+                    "id": "5ed78889acdaf970289ac000",
+                    "closed": false,
+                    "url": "",
+                    "lists": [
+                        {"name": "Other", "id": "other", "closed": false},
+                        {"name": "Garden", "id": "garden", "closed": false},
+                    ],
+                    "cards": [{
+                        "id": "5ed78889acdaf970289ac894",
+                        "name": "Dig up bones",
+                        "desc": "",
+                        "closed": false,
+                        "url": "https://trello.com/c/AbCd1234/7-dig-up-bones",
+                        "labels": [],
+                        "due": null,
+                        "idBoard": "5ed78889acdaf970289ac000",
+                        "idList": "garden",
+                        "idShort": 7,
+                        "pos": 16384,
+                    }],
+                    "labels": [],
+                    "members": [],
+                })
+                .to_string(),
+            )
+            .create();
+
         let params = TrelloParams {
-            board_name: Some("TODO"),
-            list_name: Some("back"),
+            board_name: Some("https://trello.com/c/AbCd1234/7-dig-up-bones"),
+            list_name: None,
             card_name: None,
             ignore_case: true,
+            fuzzy: false,
+            prompt: false,
         };
         let config = ClientConfig::new(&mockito::server_url(), "token", "key");
         let client = TrelloClient::new(config);
 
         let result = get_trello_object(&client, &params)?;
-        let expected = TrelloResult {
-            board: Some(Board::new(
-                "abc-def",
-                "TODO",
-                Some(vec![List::new("bcklg", "Backlog", Some(vec![]))]),
-                "",
-            )),
-            list: Some(List::new("bcklg", "Backlog", Some(vec![]))),
-            card: None,
+
+        assert_eq!(result.board.map(|b| b.name), Some(String::from("Dog")));
+        assert_eq!(result.list.map(|l| l.name), Some(String::from("Garden")));
+        let card = result.card.ok_or("Card not found")?;
+        assert_eq!(card.name, "Dig up bones");
+        assert_eq!(card.id_short, Some(7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_url_with_list_name() {
+        let params = TrelloParams {
+            board_name: Some("https://trello.com/c/AbCd1234/7-dig-up-bones"),
+            list_name: Some("Garden"),
+            card_name: None,
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
         };
+        let config = ClientConfig::new("", "", "");
+        let client = TrelloClient::new(config);
 
-        assert_eq!(result, expected);
+        let result = get_trello_object(&client, &params);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_wildcard_board_requires_card_reference() {
+        let params = TrelloParams {
+            board_name: Some("-"),
+            list_name: Some("-"),
+            card_name: Some("Dig up bones"),
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
+        };
+        let config = ClientConfig::new("", "", "");
+        let client = TrelloClient::new(config);
 
+        let result = get_trello_object(&client, &params);
+        assert!(result.is_err());
+    }
+}
+
+mod test_reference {
+    use super::*;
+
+    #[test]
+    fn test_card_url() {
+        assert_eq!(
+            Reference::parse("https://trello.com/c/AbCd1234/42-some-card"),
+            Reference::CardUrl("AbCd1234")
+        );
+        assert_eq!(
+            Reference::parse("https://trello.com/c/AbCd1234"),
+            Reference::CardUrl("AbCd1234")
+        );
+    }
+
+    #[test]
+    fn test_board_url() {
+        assert_eq!(
+            Reference::parse("https://trello.com/b/XyZ98765/my-board"),
+            Reference::BoardUrl("XyZ98765")
+        );
+    }
+
+    #[test]
+    fn test_id() {
+        assert_eq!(
+            Reference::parse("5ed78889acdaf970289ac894"),
+            Reference::Id("5ed78889acdaf970289ac894")
+        );
+    }
+
+    #[test]
+    fn test_card_number() {
+        assert_eq!(Reference::parse("#42"), Reference::CardNumber(42));
+        assert_eq!(Reference::parse("#abc"), Reference::Name("#abc"));
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(Reference::parse("TODO"), Reference::Name("TODO"));
+        assert_eq!(
+            Reference::parse("https://example.com/c/AbCd1234"),
+            Reference::Name("https://example.com/c/AbCd1234")
+        );
+    }
+}
+
+mod test_get_card {
+    use super::*;
+
+    fn params() -> TrelloParams<'static> {
+        TrelloParams {
+            board_name: None,
+            list_name: None,
+            card_name: None,
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
+        }
+    }
+
+    fn cards() -> Vec<Card> {
+        let mut first = Card::new(
+            "1",
+            "Walk the dog",
+            "",
+            None,
+            "https://trello.com/c/AbCd1234/3-walk-the-dog",
+            None,
+        );
+        first.id_short = Some(3);
+        let mut second = Card::new(
+            "2",
+            "Feed the cat",
+            "",
+            None,
+            "https://trello.com/c/QwErTy12/4-feed-the-cat",
+            None,
+        );
+        second.id_short = Some(4);
+        vec![first, second]
+    }
+
+    #[test]
+    fn test_card_number() -> TestResult {
+        let cards = cards();
+        assert_eq!(get_card(&cards, "#4", &params())?, &cards[1]);
+        assert_eq!(
+            get_card(&cards, "#5", &params()),
+            Err(FindError::NotFound("Card #5 not found".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_link() -> TestResult {
+        let cards = cards();
+        assert_eq!(get_card(&cards, "QwErTy12", &params())?, &cards[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_name() -> TestResult {
+        let cards = cards();
+        assert_eq!(get_card(&cards, "dog", &params())?, &cards[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wildcard() -> TestResult {
+        let cards = cards();
+        assert_eq!(get_card(&cards[1..], "**", &params())?, &cards[1]);
+        assert_eq!(
+            get_card(&cards, "**", &params()),
+            Err(FindError::Multiple(
+                "More than one Card found. Specify a more precise filter than '**' \
+                 (Found 'Walk the dog', 'Feed the cat')"
+                    .to_string()
+            ))
+        );
+        assert!(matches!(
+            get_card(&[], "**", &params()),
+            Err(FindError::NotFound(_))
+        ));
         Ok(())
     }
 }
@@ -126,7 +459,7 @@ mod test_get_object_by_name {
     #[test]
     fn test_more_than_one() {
         let boards = vec![
-            Board::new("1", "red", None, ""),
+            Board::new("1", "redder", None, ""),
             Board::new("2", "reddish", None, ""),
         ];
         let result = get_object_by_name(&boards, "red", false);
@@ -134,7 +467,7 @@ mod test_get_object_by_name {
         assert_eq!(
             result,
             Err(FindError::Multiple(
-                "More than one Board found. Specify a more precise filter than 'red' (Found 'red', 'reddish')".to_string()
+                "More than one Board found. Specify a more precise filter than 'red' (Found 'redder', 'reddish')".to_string()
             ))
         );
     }
@@ -171,4 +504,218 @@ mod test_get_object_by_name {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_prefers_exact_match() -> TestResult {
+        let lists = vec![
+            List::new("1", "Done (archive)", None),
+            List::new("2", "Done", None),
+        ];
+        let result = get_object_by_name(&lists, "done", true)?;
+
+        assert_eq!(result, &lists[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefers_full_match() -> TestResult {
+        let lists = vec![
+            List::new("1", "Doing later", None),
+            List::new("2", "Doing", None),
+            List::new("3", "Done", None),
+        ];
+        let result = get_object_by_name(&lists, "do.ng", true)?;
+
+        assert_eq!(result, &lists[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefers_prefix_match() -> TestResult {
+        let lists = vec![
+            List::new("1", "Not today", None),
+            List::new("2", "Today and tomorrow", None),
+        ];
+        let result = get_object_by_name(&lists, "today", true)?;
+
+        assert_eq!(result, &lists[1]);
+        Ok(())
+    }
+}
+
+mod test_select_object_by_name {
+    use super::*;
+
+    fn params(fuzzy: bool) -> TrelloParams<'static> {
+        TrelloParams {
+            board_name: None,
+            list_name: None,
+            card_name: None,
+            ignore_case: true,
+            fuzzy,
+            prompt: false,
+        }
+    }
+
+    #[test]
+    fn test_regex() -> TestResult {
+        let boards = vec![
+            Board::new("1", "Groceries", None, ""),
+            Board::new("2", "Garden", None, ""),
+        ];
+        assert_eq!(
+            select_object_by_name(&boards, "g.*n", &params(false))?,
+            &boards[1]
+        );
+        assert_eq!(
+            select_object_by_name(&boards, "grcs", &params(false)),
+            Err(FindError::NotFound(
+                "Board not found. Specify a more precise filter than 'grcs'".to_string()
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuzzy() -> TestResult {
+        let boards = vec![
+            Board::new("1", "Groceries", None, ""),
+            Board::new("2", "Garden", None, ""),
+            Board::new("3", "Gardening Club", None, ""),
+        ];
+        assert_eq!(
+            select_object_by_name(&boards, "grcs", &params(true))?,
+            &boards[0]
+        );
+        // shorter names win ties
+        assert_eq!(
+            select_object_by_name(&boards, "gard", &params(true))?,
+            &boards[1]
+        );
+        assert_eq!(
+            select_object_by_name(&boards, "gclub", &params(true))?,
+            &boards[2]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuzzy_ambiguous() {
+        let boards = vec![
+            Board::new("1", "Work", None, ""),
+            Board::new("2", "Wiki", None, ""),
+        ];
+        let result = select_object_by_name(&boards, "w", &params(true));
+
+        assert_eq!(
+            result,
+            Err(FindError::Multiple(
+                "More than one Board found. Specify a more precise filter than 'w' (Found 'Work', 'Wiki')".to_string()
+            ))
+        );
+    }
+}
+
+mod test_fuzzy_score {
+    use super::*;
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(fuzzy_score("abc", "acb", true), None);
+        assert_eq!(fuzzy_score("A", "abc", false), None);
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        assert!(fuzzy_score("A", "abc", true).is_some());
+    }
+
+    #[test]
+    fn test_consecutive_scores_higher() {
+        let consecutive = fuzzy_score("bar", "foobar", true);
+        let scattered = fuzzy_score("bar", "bxaxr", true);
+
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn test_word_start_scores_higher() {
+        let word_start = fuzzy_score("pr", "Pull Requests", true);
+        let middle = fuzzy_score("pr", "uppercase", true);
+
+        assert!(word_start > middle);
+    }
+}
+
+mod test_find_in_board {
+    use super::*;
+
+    fn board() -> Board {
+        let lists = vec![
+            List::new(
+                "L1",
+                "Today",
+                Some(vec![Card::new("C1", "Walk the dog", "", None, "", None)]),
+            ),
+            List::new(
+                "L2",
+                "Done",
+                Some(vec![Card::new("C2", "Feed the cat", "", None, "", None)]),
+            ),
+        ];
+        Board::new("B1", "TODO", Some(lists), "")
+    }
+
+    fn params<'a>(list_name: Option<&'a str>, card_name: Option<&'a str>) -> TrelloParams<'a> {
+        TrelloParams {
+            board_name: Some("TODO"),
+            list_name,
+            card_name,
+            ignore_case: true,
+            fuzzy: false,
+            prompt: false,
+        }
+    }
+
+    #[test]
+    fn test_list_wildcard() -> TestResult {
+        for wildcard in ["-", "**"] {
+            let result = find_in_board(board(), &params(Some(wildcard), Some("cat")))?;
+
+            assert_eq!(result.list.map(|l| l.id), Some(String::from("L2")));
+            assert_eq!(result.card.map(|c| c.id), Some(String::from("C2")));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_wildcard_without_card() {
+        let result = find_in_board(board(), &params(Some("**"), None));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_card_wildcard() -> TestResult {
+        let result = find_in_board(board(), &params(Some("done"), Some("**")))?;
+
+        assert_eq!(result.list.map(|l| l.id), Some(String::from("L2")));
+        assert_eq!(result.card.map(|c| c.id), Some(String::from("C2")));
+
+        let result = find_in_board(board(), &params(Some("**"), Some("**")));
+        assert!(matches!(
+            result.map_err(|e| e.downcast::<FindError>().map(|e| *e)),
+            Err(Ok(FindError::Multiple(_)))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_and_card() -> TestResult {
+        let result = find_in_board(board(), &params(Some("today"), Some("dog")))?;
+
+        assert_eq!(result.list.map(|l| l.id), Some(String::from("L1")));
+        assert_eq!(result.card.map(|c| c.id), Some(String::from("C1")));
+        Ok(())
+    }
 }
@@ -0,0 +1,70 @@
+use crate::output::OutputFormat;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Object {
+    id: u32,
+    id_board: &'static str,
+}
+
+fn objects() -> Vec<Object> {
+    vec![
+        Object {
+            id: 1,
+            id_board: "b",
+        },
+        Object {
+            id: 2,
+            id_board: "b",
+        },
+    ]
+}
+
+fn write_objects(format: OutputFormat) -> String {
+    let mut buffer = vec![];
+    format.write_objects(&mut buffer, &objects()).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn test_text_writes_nothing() {
+    assert_eq!(write_objects(OutputFormat::Text), "");
+}
+
+#[test]
+fn test_json_writes_array() {
+    let expected = r#"[
+  {
+    "id": 1,
+    "idBoard": "b"
+  },
+  {
+    "id": 2,
+    "idBoard": "b"
+  }
+]
+"#;
+    assert_eq!(write_objects(OutputFormat::Json), expected);
+}
+
+#[test]
+fn test_jsonl_writes_line_per_object() {
+    assert_eq!(
+        write_objects(OutputFormat::Jsonl),
+        "{\"id\":1,\"idBoard\":\"b\"}\n{\"id\":2,\"idBoard\":\"b\"}\n"
+    );
+}
+
+#[test]
+fn test_jsonl_single_object() {
+    let mut buffer = vec![];
+    OutputFormat::Jsonl
+        .write_object(&mut buffer, &objects()[0])
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "{\"id\":1,\"idBoard\":\"b\"}\n"
+    );
+}
@@ -0,0 +1,138 @@
+use crate::paths::*;
+use clap::{App, Arg};
+use std::ffi::OsString;
+
+fn app() -> App<'static> {
+    App::new("tro")
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .takes_value(true)
+                .global(true),
+        )
+        .subcommand(
+            App::new("attach")
+                .arg(Arg::new("board_name").required(true))
+                .arg(Arg::new("list_name").required(true))
+                .arg(Arg::new("card_name").required(true))
+                .arg(Arg::new("name").short('n').long("name").takes_value(true))
+                .arg(Arg::new("case_sensitive").short('c'))
+                .arg(Arg::new("path").required(true).multiple_values(true)),
+        )
+        .subcommand(App::new("export").arg(Arg::new("board_name").required(true)))
+}
+
+fn expand(args: &[&str]) -> Vec<String> {
+    let args = args.iter().map(OsString::from).collect();
+    expand_paths(&app(), args)
+        .into_iter()
+        .map(|a| a.into_string().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_split_path() {
+    assert_eq!(
+        split_path("TODO/today/rust"),
+        Some(vec![
+            String::from("TODO"),
+            String::from("today"),
+            String::from("rust")
+        ])
+    );
+    assert_eq!(
+        split_path("TODO/**"),
+        Some(vec![String::from("TODO"), String::from("**")])
+    );
+    assert_eq!(split_path("TODO"), None);
+}
+
+#[test]
+fn test_split_path_escapes() {
+    assert_eq!(
+        split_path(r"Q1\/Q2/to do/\d+ bugs"),
+        Some(vec![
+            String::from("Q1/Q2"),
+            String::from("to do"),
+            String::from(r"\d+ bugs"),
+        ])
+    );
+    assert_eq!(
+        split_path(r"back\\slash/list"),
+        Some(vec![String::from(r"back\slash"), String::from("list")])
+    );
+}
+
+#[test]
+fn test_split_path_url() {
+    assert_eq!(split_path("https://trello.com/c/AbCd1234/12-card"), None);
+}
+
+#[test]
+fn test_expand_paths() {
+    assert_eq!(
+        expand(&["tro", "attach", "TODO/today/rust", "file.txt"]),
+        vec!["tro", "attach", "TODO", "today", "rust", "file.txt"]
+    );
+}
+
+#[test]
+fn test_expand_paths_with_flags() {
+    assert_eq!(
+        expand(&[
+            "tro",
+            "-o",
+            "json",
+            "attach",
+            "-c",
+            "-n",
+            "a/b",
+            "TODO/today/rust",
+            "x"
+        ]),
+        vec![
+            "tro", "-o", "json", "attach", "-c", "-n", "a/b", "TODO", "today", "rust", "x"
+        ]
+    );
+}
+
+#[test]
+fn test_expand_paths_unchanged() {
+    // positional arguments keep working
+    let args = ["tro", "attach", "TODO", "today", "rust", "file/path.txt"];
+    assert_eq!(expand(&args), args);
+
+    // subcommands which only take a board
+    let args = ["tro", "export", "a/b"];
+    assert_eq!(expand(&args), args);
+
+    // too many components
+    let args = ["tro", "attach", "a/b/c/d", "x"];
+    assert_eq!(expand(&args), args);
+}
+
+#[test]
+fn test_expand_paths_ambiguous() {
+    // board names containing slashes keep working as separate patterns
+    let args = ["tro", "attach", "Q1/Q2", "list", "card", "file.txt"];
+    assert_eq!(expand(&args), args);
+
+    let args = ["tro", "attach", "Q1/Q2/Q3", "list", "card", "file.txt"];
+    assert_eq!(expand(&args), args);
+
+    let args = [
+        "tro", "attach", "-n", "x", "Q1/Q2", "list", "-c", "card", "file.txt",
+    ];
+    assert_eq!(expand(&args), args);
+
+    // a list pattern follows the path
+    let args = ["tro", "attach", "Q1/Q2", "card"];
+    assert_eq!(expand(&args), args);
+
+    // too few arguments for separate patterns
+    assert_eq!(
+        expand(&["tro", "attach", "TODO/today/rust", "a.txt", "b.txt"]),
+        vec!["tro", "attach", "TODO", "today", "rust", "a.txt", "b.txt"]
+    );
+}
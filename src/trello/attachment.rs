@@ -3,16 +3,30 @@ use crate::formatting::header;
 use crate::trello_error::TrelloError;
 use crate::trello_object::{Renderable, TrelloObject};
 
-use serde::Deserialize;
+use chrono::{DateTime, Local, Utc};
+use reqwest::blocking::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use unicode_width::UnicodeWidthStr;
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Attachment {
     pub id: String,
     pub name: String,
     pub url: String,
+    /// Size of uploaded files in bytes. Links have no size.
+    #[serde(default)]
+    pub bytes: Option<u64>,
+    /// Trello sends an empty string rather than null for links
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub is_upload: bool,
 }
 
 impl Attachment {
@@ -22,7 +36,7 @@ impl Attachment {
             &[("fields", &Attachment::get_fields().join(","))],
         )?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 
     pub fn apply(client: &TrelloClient, card_id: &str, file: &str) -> Result<Attachment> {
@@ -30,16 +44,178 @@ impl Attachment {
             .config
             .get_trello_url(&format!("/1/cards/{}/attachments", card_id), &[])?;
 
-        let form = reqwest::blocking::multipart::Form::new().file("file", file)?;
+        let form = Form::new().file("file", file)?;
 
         Ok(client
-            .client
-            .post(url)
-            .multipart(form)
-            .send()?
-            .error_for_status()?
+            .send(client.client.post(url).multipart(form))?
             .json()?)
     }
+
+    /// Uploads the contents of a reader (such as stdin) to a card as a file with
+    /// the given name
+    pub fn upload<R: Read + Send + 'static>(
+        client: &TrelloClient,
+        card_id: &str,
+        file_name: &str,
+        reader: R,
+    ) -> Result<Attachment> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/attachments", card_id), &[])?;
+
+        let part = Part::reader(reader).file_name(String::from(file_name));
+        let form = Form::new()
+            .text("name", String::from(file_name))
+            .part("file", part);
+
+        Ok(client
+            .send(client.client.post(url).multipart(form))?
+            .json()?)
+    }
+
+    pub fn delete(client: &TrelloClient, card_id: &str, attachment_id: &str) -> Result<()> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/attachments/{}", card_id, attachment_id),
+            &[],
+        )?;
+
+        client.send(client.client.delete(url))?;
+
+        Ok(())
+    }
+
+    /// Size of the attachment in a human readable format, e.g. "1.5 MB"
+    pub fn render_size(&self) -> String {
+        let bytes = match self.bytes {
+            Some(bytes) => bytes,
+            None => return String::from("-"),
+        };
+        if bytes < 1024 {
+            return format!("{} B", bytes);
+        }
+
+        let mut size = bytes as f64 / 1024.0;
+        for unit in ["KB", "MB", "GB"] {
+            if size < 1024.0 || unit == "GB" {
+                return format!("{:.1} {}", size, unit);
+            }
+            size /= 1024.0;
+        }
+        unreachable!()
+    }
+
+    /// Type of the attachment: the mime type of uploaded files or "link"
+    pub fn render_type(&self) -> &str {
+        match self.mime_type.as_deref() {
+            _ if !self.is_upload => "link",
+            Some(mime_type) if !mime_type.is_empty() => mime_type,
+            _ => "-",
+        }
+    }
+
+    /// Renders attachments as a table showing their size, type and date
+    pub fn render_table(attachments: &[Attachment]) -> String {
+        let rows = attachments
+            .iter()
+            .map(|a| {
+                [
+                    a.name.clone(),
+                    a.render_size(),
+                    String::from(a.render_type()),
+                    a.date
+                        .map(|d| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|| String::from("-")),
+                    a.url.clone(),
+                ]
+            })
+            .collect::<Vec<[String; 5]>>();
+
+        let titles = ["Name", "Size", "Type", "Date", "Url"].map(String::from);
+        let mut widths = titles.clone().map(|t| UnicodeWidthStr::width(t.as_str()));
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(UnicodeWidthStr::width(cell.as_str()));
+            }
+        }
+
+        std::iter::once(&titles)
+            .chain(&rows)
+            .map(|row| {
+                row.iter()
+                    .zip(widths)
+                    .enumerate()
+                    .map(|(i, (cell, width))| {
+                        let padding = " ".repeat(width - UnicodeWidthStr::width(cell.as_str()));
+                        match i {
+                            // right align sizes
+                            1 => format!("{}{}", padding, cell),
+                            _ => format!("{}{}", cell, padding),
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Name of the uploaded file of the attachment, as it appears in its download url.
+    /// Links attached to a card have no file.
+    pub fn file_name(&self) -> Option<&str> {
+        self.url
+            .split_once("/download/")
+            .map(|(_, name)| name)
+            .filter(|name| !name.is_empty() && !name.contains('/'))
+    }
+
+    /// Downloads the uploaded file of an attachment, streaming its contents to the
+    /// given writer, and returns the number of bytes written. Trello only serves
+    /// uploads to authenticated requests, so the file is requested through the API
+    /// with the credentials of the client. Links have no file and fail with
+    /// `TrelloError::Attachment`.
+    pub fn download<W: Write + ?Sized>(
+        client: &TrelloClient,
+        card_id: &str,
+        attachment: &Attachment,
+        writer: &mut W,
+    ) -> Result<u64> {
+        let file_name = attachment.file_name().ok_or_else(|| {
+            TrelloError::Attachment(format!(
+                "'{}' is a link and has no file to download",
+                attachment.name
+            ))
+        })?;
+
+        let url = client.config.get_trello_url(
+            &format!(
+                "/1/cards/{}/attachments/{}/download/{}",
+                card_id, attachment.id, file_name
+            ),
+            &[],
+        )?;
+
+        let mut response = client.send(client.client.get(url))?;
+
+        Ok(response.copy_to(writer)?)
+    }
+
+    /// Attaches a link to the given url to a card
+    pub fn link(
+        client: &TrelloClient,
+        card_id: &str,
+        link: &str,
+        name: &str,
+    ) -> Result<Attachment> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/attachments", card_id), &[])?;
+
+        let params = [("url", link), ("name", name)];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
 }
 
 impl TrelloObject for Attachment {
@@ -52,7 +228,7 @@ impl TrelloObject for Attachment {
     }
 
     fn get_fields() -> &'static [&'static str] {
-        &["id", "name", "url"]
+        &["id", "name", "url", "bytes", "mimeType", "date", "isUpload"]
     }
 }
 
@@ -0,0 +1,291 @@
+use crate::trello_error::TrelloError;
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use url::Url;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+const AUTHORIZE_URL: &str = "https://trello.com/1/authorize";
+
+/// Largest request body accepted by the loopback listener
+const MAX_BODY_LENGTH: usize = 64 * 1024;
+
+/// Page served in place of the redirect from Trello. Trello passes the token in
+/// the fragment of the url, which browsers never send, so the page posts the
+/// fragment to the listener. The fragment is removed from the url first, so that
+/// the token is not kept in the browser history.
+const REDIRECT_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>tro</title></head>
+<body>
+<p id="message">Waiting for authorization from Trello...</p>
+<script>
+var params = window.location.hash.substring(1);
+history.replaceState(null, "", window.location.pathname);
+if (params.length > 0) {
+  fetch(window.location.pathname, {
+    method: "POST",
+    headers: { "Content-Type": "application/x-www-form-urlencoded" },
+    body: params
+  })
+    .then(function (response) { return response.text(); })
+    .then(function (page) {
+      document.open();
+      document.write(page);
+      document.close();
+    })
+    .catch(function () {
+      document.getElementById("message").textContent = "Unable to pass the token to tro.";
+    });
+} else {
+  document.getElementById("message").textContent = "No token was received from Trello.";
+}
+</script>
+</body>
+</html>
+"#;
+
+const AUTHORIZED_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>tro</title></head>
+<body><p>tro has been authorized. You can close this window.</p></body>
+</html>
+"#;
+
+const DENIED_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>tro</title></head>
+<body><p>tro was not authorized. You can close this window.</p></body>
+</html>
+"#;
+
+/// Determines what is shown to users on the Trello authorize page and what the
+/// resulting token allows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizeOptions {
+    /// Name of the application shown on the authorize page
+    pub app_name: String,
+    /// Comma separated permissions of the token, e.g. `read,write`
+    pub scope: String,
+    /// Lifetime of the token: `1hour`, `1day`, `30days` or `never`
+    pub expiration: String,
+}
+
+impl Default for AuthorizeOptions {
+    fn default() -> Self {
+        AuthorizeOptions {
+            app_name: String::from("tro"),
+            scope: String::from("read,write"),
+            expiration: String::from("never"),
+        }
+    }
+}
+
+impl AuthorizeOptions {
+    /// Url of the page on which users authorize the application with the given
+    /// API key. Trello redirects to the return url with the token once authorized.
+    /// ```
+    /// let options = trello::AuthorizeOptions::default();
+    /// let url = options.url("some-key", "http://127.0.0.1:8080/")?;
+    /// assert_eq!(
+    ///     url.to_string(),
+    ///     "https://trello.com/1/authorize?key=some-key&name=tro&scope=read%2Cwrite\
+    ///      &expiration=never&response_type=token&callback_method=fragment\
+    ///      &return_url=http%3A%2F%2F127.0.0.1%3A8080%2F"
+    /// );
+    /// # Ok::<(), url::ParseError>(())
+    /// ```
+    pub fn url(&self, key: &str, return_url: &str) -> std::result::Result<Url, url::ParseError> {
+        Url::parse_with_params(
+            AUTHORIZE_URL,
+            &[
+                ("key", key),
+                ("name", &self.app_name),
+                ("scope", &self.scope),
+                ("expiration", &self.expiration),
+                ("response_type", "token"),
+                ("callback_method", "fragment"),
+                ("return_url", return_url),
+            ],
+        )
+    }
+}
+
+/// HTTP server on the loopback interface to which Trello redirects the browser
+/// of the user once they authorize tro, so that the token can be captured
+/// without being copied by hand.
+///
+/// The return url contains a random path, so that other local processes cannot
+/// hand the listener a token of their own without knowing the url.
+#[derive(Debug)]
+pub struct LoopbackListener {
+    listener: TcpListener,
+    nonce: String,
+}
+
+/// What Trello redirected the browser with
+enum Callback {
+    Token(String),
+    Denied(String),
+}
+
+impl LoopbackListener {
+    /// Listens on the given port, or on a port chosen by the operating system
+    /// when the port is 0
+    pub fn bind(port: u16) -> Result<LoopbackListener> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        listener.set_nonblocking(true)?;
+
+        Ok(LoopbackListener {
+            listener,
+            nonce: nonce(),
+        })
+    }
+
+    /// Url which Trello should redirect to after authorization
+    pub fn return_url(&self) -> Result<String> {
+        Ok(format!(
+            "http://{}/{}/",
+            self.listener.local_addr()?,
+            self.nonce
+        ))
+    }
+
+    /// Serves requests until one carries a token, which is returned. Fails when
+    /// the user denies access or no token is received within the timeout.
+    /// Connections which fail or send unexpected requests are ignored.
+    pub fn wait_for_token(&self, timeout: Duration) -> Result<String> {
+        let start = Instant::now();
+        let (sender, receiver) = mpsc::channel();
+        loop {
+            match receiver.try_recv() {
+                Ok(Callback::Token(token)) => return Ok(token),
+                Ok(Callback::Denied(error)) => {
+                    return Err(TrelloError::Authorize(format!(
+                        "Trello did not authorize tro: {}",
+                        error
+                    )));
+                }
+                Err(_) if start.elapsed() >= timeout => {
+                    return Err(TrelloError::Authorize(String::from(
+                        "Timed out waiting for authorization",
+                    )));
+                }
+                Err(_) => {}
+            }
+
+            match self.listener.accept() {
+                Ok((stream, _)) => self.serve(stream, sender.clone()),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    warn!("Loopback listener failed to accept a connection: {}", e);
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+    }
+
+    /// Serves the connection in the background, so that a connection which never
+    /// sends a request (e.g. one opened ahead of time by the browser) does not
+    /// hold up the others
+    fn serve(&self, stream: TcpStream, sender: Sender<Callback>) {
+        let nonce = self.nonce.clone();
+        thread::spawn(move || match handle_request(stream, &nonce) {
+            Ok(Some(callback)) => {
+                // the receiver is gone once another request carried the result
+                let _ = sender.send(callback);
+            }
+            Ok(None) => {}
+            Err(e) => debug!("Loopback listener failed to serve a request: {}", e),
+        });
+    }
+}
+
+/// Random path segment of the return url. `RandomState` is seeded by the
+/// operating system, so its hashes are unpredictable to other processes.
+fn nonce() -> String {
+    let state = RandomState::new();
+    (0..2u8)
+        .map(|i| format!("{:016x}", state.hash_one(i)))
+        .collect()
+}
+
+/// Responds to a single request, returning what Trello redirected with if any
+fn handle_request(mut stream: TcpStream, nonce: &str) -> Result<Option<Callback>> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // only the length of the body is needed from the headers
+    let mut content_length = 0;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or_default();
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET");
+    let target = parts.next().unwrap_or("/");
+    let url = Url::parse("http://localhost")?.join(target)?;
+    debug!("Loopback listener received {} {}", method, url.path());
+
+    if url.path() != format!("/{}/", nonce) {
+        respond(&mut stream, "404 Not Found", "")?;
+        return Ok(None);
+    }
+    if method != "POST" {
+        respond(&mut stream, "200 OK", REDIRECT_PAGE)?;
+        return Ok(None);
+    }
+
+    if content_length > MAX_BODY_LENGTH {
+        respond(&mut stream, "413 Payload Too Large", "")?;
+        return Ok(None);
+    }
+
+    // the page posts the fragment of the redirect as a form
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let param = |name: &str| {
+        url::form_urlencoded::parse(&body)
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.into_owned())
+    };
+    if let Some(token) = param("token").filter(|t| !t.is_empty()) {
+        respond(&mut stream, "200 OK", AUTHORIZED_PAGE)?;
+        return Ok(Some(Callback::Token(token)));
+    }
+    if let Some(error) = param("error") {
+        respond(&mut stream, "200 OK", DENIED_PAGE)?;
+        return Ok(Some(Callback::Denied(error)));
+    }
+
+    respond(&mut stream, "400 Bad Request", "")?;
+    Ok(None)
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(stream.flush()?)
+}
@@ -1,23 +1,50 @@
+use crate::card::Card;
 use crate::client::TrelloClient;
 use crate::formatting::title;
+use crate::label::Label;
 use crate::list::List;
+use crate::member::Member;
 use crate::trello_error::TrelloError;
 use crate::trello_object::{Renderable, TrelloObject};
 
 use colored::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
 // https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#board-object
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Board {
     pub id: String,
     pub name: String,
     pub closed: bool,
     pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub lists: Option<Vec<List>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<Label>>,
+}
+
+/// Board retrieved along with its nested resources. Trello returns the cards of
+/// a board alongside its lists rather than within them.
+#[derive(Deserialize)]
+struct NestedBoard {
+    lists: Vec<List>,
+    #[serde(default)]
+    cards: Vec<NestedCard>,
+    labels: Vec<Label>,
+    members: Vec<Member>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NestedCard {
+    id_list: String,
+    #[serde(default)]
+    pos: f64,
+    #[serde(flatten)]
+    card: Card,
 }
 
 impl TrelloObject for Board {
@@ -61,6 +88,7 @@ impl Board {
             name: String::from(name),
             url: String::from(url),
             lists,
+            labels: None,
             closed: false,
         }
     }
@@ -74,32 +102,115 @@ impl Board {
         result
     }
 
-    /// Retrieves any missing nested content for the given board. This potentially
-    /// means one or more network requests in order to retrieve the data. The Board
-    /// will be mutated to include all its associated lists. The lists will also in turn
-    /// contain the associated card resources.
+    /// Retrieves any missing nested content for the given board in a single request.
+    /// The Board will be mutated to include all its associated lists and labels. The
+    /// lists will also in turn contain the associated card resources, with their
+    /// assigned members populated.
     pub fn retrieve_nested(&mut self, client: &TrelloClient) -> Result<()> {
-        if self.lists.is_none() {
-            debug!("Retrieving nested data for board: {}", self.id);
-            self.lists = Some(List::get_all(client, &self.id, true)?);
-        } else {
+        let has_cards = self
+            .lists
+            .as_ref()
+            .is_some_and(|lists| lists.iter().all(|l| l.cards.is_some()));
+        if has_cards {
             debug!("No need to retrieve nested data");
+            return Ok(());
+        }
+
+        debug!("Retrieving nested data for board: {}", self.id);
+        let nested = self.get_nested(client, true)?;
+
+        let mut cards = nested.cards;
+        cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut lists = nested.lists;
+        for list in &mut lists {
+            list.cards = Some(
+                cards
+                    .iter()
+                    .filter(|c| c.id_list == list.id)
+                    .map(|c| {
+                        let mut card = c.card.clone();
+                        card.populate_members(&nested.members);
+                        card
+                    })
+                    .collect(),
+            );
         }
+        self.lists = Some(lists);
+        self.labels = Some(nested.labels);
         Ok(())
     }
 
+    /// Retrieves the lists and labels of the board, without their cards, in a single
+    /// request. Unlike cards, the structure of a board changes rarely and is served
+    /// from the cache of the client when enabled.
+    pub fn retrieve_structure(&mut self, client: &TrelloClient) -> Result<()> {
+        if self.lists.is_some() {
+            debug!("No need to retrieve board structure");
+            return Ok(());
+        }
+
+        debug!("Retrieving structure of board: {}", self.id);
+        let nested = self.get_nested(client, false)?;
+        self.lists = Some(nested.lists);
+        self.labels = Some(nested.labels);
+        Ok(())
+    }
+
+    fn get_nested(&self, client: &TrelloClient, cards: bool) -> Result<NestedBoard> {
+        let card_fields = [Card::get_fields(), &["idList", "pos"]].concat().join(",");
+        let mut params = vec![
+            ("fields", Board::get_fields().join(",")),
+            ("lists", String::from("open")),
+            ("list_fields", List::get_fields().join(",")),
+        ];
+        if cards {
+            params.push(("cards", String::from("open")));
+            params.push(("card_fields", card_fields));
+        }
+        params.extend([
+            ("labels", String::from("all")),
+            ("label_fields", Label::get_fields().join(",")),
+            ("members", String::from("all")),
+            ("member_fields", Member::get_fields().join(",")),
+        ]);
+        let params = params
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/boards/{}", self.id), &params)?;
+
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+
     pub fn create(client: &TrelloClient, name: &str) -> Result<Board> {
         let url = client.config.get_trello_url("/1/boards/", &[])?;
 
         let params = [("name", name)];
 
-        Ok(client
-            .client
-            .post(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
+
+    /// Creates a board, specifying whether Trello should add its default lists
+    /// (To Do, Doing and Done) and its default unnamed labels to it.
+    pub fn create_with_defaults(
+        client: &TrelloClient,
+        name: &str,
+        default_lists: bool,
+        default_labels: bool,
+    ) -> Result<Board> {
+        let url = client.config.get_trello_url("/1/boards/", &[])?;
+
+        let params = [
+            ("name", name),
+            ("defaultLists", &default_lists.to_string()),
+            ("defaultLabels", &default_labels.to_string()),
+        ];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
     }
 
     pub fn open(client: &TrelloClient, board_id: &str) -> Result<Board> {
@@ -109,13 +220,7 @@ impl Board {
 
         let params = [("closed", "false")];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     pub fn update(client: &TrelloClient, board: &Board) -> Result<Board> {
@@ -125,13 +230,7 @@ impl Board {
 
         let params = [("name", &board.name), ("closed", &board.closed.to_string())];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     pub fn get_all(client: &TrelloClient) -> Result<Vec<Board>> {
@@ -143,7 +242,7 @@ impl Board {
             ],
         )?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 
     pub fn get(client: &TrelloClient, board_id: &str) -> Result<Board> {
@@ -152,6 +251,6 @@ impl Board {
             &[("fields", &Board::get_fields().join(","))],
         )?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 }
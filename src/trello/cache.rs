@@ -0,0 +1,258 @@
+use crate::cassette::{RecordedResponse, record_response, to_response};
+use crate::transport::Transport;
+use crate::trello_error::TrelloError;
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Method;
+use reqwest::blocking::{Request, Response};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use url::Url;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+const CACHE_VERSION: u32 = 1;
+
+/// Configures the on-disk cache of boards and their lists, labels and members,
+/// which saves requests to Trello when looking up objects by name
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Number of seconds for which cached responses are used
+    pub ttl_secs: u64,
+    /// Directory in which cache files are stored. Defaults to the `cache`
+    /// directory within the configuration directory of tro.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// Ignore cached responses, while still caching new ones
+    #[serde(skip)]
+    pub refresh: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            ttl_secs: 300,
+            dir: None,
+            refresh: false,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Path of the cache file of an account within the given directory. Each
+    /// combination of API key and token has its own cache file, so that cached
+    /// objects are never shared between accounts. The credentials are hashed
+    /// rather than stored, with a hash which does not change between releases of
+    /// Rust so that cache files keep being found.
+    pub fn path(dir: &Path, host: &str, key: &str, token: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        for value in [host, key, token] {
+            hasher.update(value.as_bytes());
+            hasher.update([0]);
+        }
+        let name = hasher.finalize()[..16]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        dir.join(format!("{}.json", name))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+struct CacheEntry {
+    stored_at: DateTime<Utc>,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+struct CacheFile {
+    version: u32,
+    /// Cached responses keyed by the url of their request
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl Default for CacheFile {
+    fn default() -> Self {
+        CacheFile {
+            version: CACHE_VERSION,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+/// Whether the response to a request of the given url is cached. Only the boards
+/// of the user, and the lists, labels, members and custom fields of boards are
+/// cached. Cards change too often, so requests which include them never are.
+fn is_cacheable(url: &Url) -> bool {
+    if url.query_pairs().any(|(name, _)| name == "cards") {
+        return false;
+    }
+
+    let segments = url
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>();
+
+    matches!(
+        segments.as_slice(),
+        ["1", "members", "me", "boards"]
+            | ["1", "boards", _]
+            | [
+                "1",
+                "boards",
+                _,
+                "lists" | "labels" | "members" | "customFields"
+            ]
+    )
+}
+
+/// Transport which serves the responses of GET requests for the structure of
+/// boards from a cache file while they are younger than the configured TTL.
+/// Other requests are sent with the inner Transport. Any request which modifies
+/// objects invalidates the whole cache, as it may change cached objects.
+#[derive(Debug)]
+pub struct CachingTransport {
+    inner: Box<dyn Transport>,
+    path: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+    /// Loaded on the first request
+    cache: Mutex<Option<CacheFile>>,
+    served_from_cache: AtomicBool,
+    cache_hits: AtomicUsize,
+}
+
+impl CachingTransport {
+    pub fn new(inner: Box<dyn Transport>, path: &Path, config: &CacheConfig) -> CachingTransport {
+        CachingTransport {
+            inner,
+            path: path.to_path_buf(),
+            ttl: Duration::seconds(config.ttl_secs.try_into().unwrap_or(i64::MAX)),
+            refresh: config.refresh,
+            cache: Mutex::new(None),
+            served_from_cache: AtomicBool::new(false),
+            cache_hits: AtomicUsize::new(0),
+        }
+    }
+
+    /// Runs the given function with the contents of the cache file, which is
+    /// loaded when first needed. An unreadable cache file is treated as empty.
+    fn with_cache<T>(&self, f: impl FnOnce(&mut CacheFile) -> T) -> T {
+        let mut cache = self.cache.lock().expect("Cache lock was poisoned");
+        let cache = cache.get_or_insert_with(|| {
+            fs::read_to_string(&self.path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<CacheFile>(&contents).ok())
+                .filter(|c| c.version == CACHE_VERSION)
+                .unwrap_or_default()
+        });
+        f(cache)
+    }
+
+    /// Saves the cache file, which is only readable and writable by its owner as
+    /// the cached boards may be private
+    fn save(&self, cache: &CacheFile) -> std::result::Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = serde_json::to_string(cache)?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // the mode only applies to new files
+            if self.path.exists() {
+                fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        options.open(&self.path)?.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+impl Transport for CachingTransport {
+    fn execute(&self, request: Request) -> Result<Response> {
+        if request.method() != Method::GET {
+            self.invalidate_cache();
+            return self.inner.execute(request);
+        }
+        if !is_cacheable(request.url()) {
+            return self.inner.execute(request);
+        }
+
+        let url = request.url().to_string();
+        if !self.refresh {
+            let cached = self.with_cache(|cache| {
+                cache
+                    .entries
+                    .get(&url)
+                    .filter(|e| Utc::now() - e.stored_at < self.ttl)
+                    .map(|e| e.response.clone())
+            });
+            if let Some(response) = cached {
+                trace!("Serving {} from cache", url);
+                self.served_from_cache.store(true, Ordering::Relaxed);
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                return to_response(&response);
+            }
+        }
+
+        let response = self.inner.execute(request)?;
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+
+        let recorded = record_response(response)?;
+        let entry = CacheEntry {
+            stored_at: Utc::now(),
+            response: recorded.clone(),
+        };
+        self.with_cache(|cache| {
+            cache.entries.insert(url, entry);
+            // a cache which cannot be written only makes tro slower
+            if let Err(e) = self.save(cache) {
+                debug!("Unable to save cache to {:?}: {}", self.path, e);
+            }
+        });
+
+        to_response(&recorded)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    fn served_from_cache(&self) -> bool {
+        self.served_from_cache.load(Ordering::Relaxed)
+    }
+
+    fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    fn invalidate_cache(&self) {
+        debug!("Invalidating cache {:?}", self.path);
+        self.served_from_cache.store(false, Ordering::Relaxed);
+        self.with_cache(|cache| cache.entries.clear());
+
+        if let Err(e) = fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            debug!("Unable to remove cache {:?}: {}", self.path, e);
+        }
+    }
+}
@@ -1,18 +1,21 @@
+use crate::checklist::{CheckItem, CheckItemState, Checklist};
 use crate::client::TrelloClient;
+use crate::custom_field::{CustomField, CustomFieldItem};
 use crate::formatting::header;
 use crate::label::Label;
+use crate::member::Member;
 use crate::trello_error::TrelloError;
 use crate::trello_object::{Renderable, TrelloObject};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Local, Utc};
 use colored::Colorize;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
 // https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#card-object
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Card {
     pub id: String,
@@ -22,6 +25,24 @@ pub struct Card {
     pub url: String,
     pub labels: Option<Vec<Label>>,
     pub due: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub due_complete: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checklists: Option<Vec<Checklist>>,
+    #[serde(default)]
+    pub id_members: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<Member>>,
+    #[serde(default)]
+    pub id_board: String,
+    /// Number of the card within its board, shown as "#42" in the Trello UI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_short: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_field_items: Option<Vec<CustomFieldItem>>,
+    /// Custom field definitions of the board the card belongs to
+    #[serde(skip)]
+    pub custom_fields: Option<Vec<CustomField>>,
 }
 
 impl TrelloObject for Card {
@@ -34,16 +55,47 @@ impl TrelloObject for Card {
     }
 
     fn get_fields() -> &'static [&'static str] {
-        &["id", "name", "desc", "labels", "closed", "due", "url"]
+        &[
+            "id",
+            "name",
+            "desc",
+            "labels",
+            "closed",
+            "due",
+            "dueComplete",
+            "url",
+            "idMembers",
+            "idBoard",
+            "idShort",
+        ]
     }
 }
 
 impl Renderable for Card {
     fn render(&self, headers: bool) -> String {
-        match headers {
-            true => [header(&self.name, "=").as_str(), &self.desc].join("\n"),
-            false => self.desc.clone(),
+        let mut result = match headers {
+            true => vec![header(&self.name, "="), self.desc.clone()],
+            false => vec![self.desc.clone()],
+        };
+        if let (Some(fields), Some(items)) = (&self.custom_fields, &self.custom_field_items) {
+            let values = fields
+                .iter()
+                .filter_map(|f| Some(format!("{}: {}", f.name, f.format_value(items)?)))
+                .collect::<Vec<String>>();
+
+            if !values.is_empty() {
+                result.push(String::from(""));
+                result.push(header(CUSTOM_FIELDS_HEADER, "-"));
+                result.extend(values);
+            }
+        }
+        if let Some(checklists) = &self.checklists {
+            for checklist in checklists {
+                result.push(String::from(""));
+                result.push(checklist.render(true));
+            }
         }
+        result.join("\n")
     }
 
     fn simple_render(&self) -> String {
@@ -65,6 +117,16 @@ impl Renderable for Card {
             }
         }
 
+        if let Some(due) = self.render_due(Utc::now()) {
+            lformat.push(due);
+        }
+
+        if let Some(members) = &self.members {
+            for m in members {
+                lformat.push(format!("[{}]", m.initials).cyan().to_string());
+            }
+        }
+
         // trim end in case there is no data presented by lformat
         lformat.join(" ").trim_end().to_string()
     }
@@ -74,6 +136,7 @@ impl Renderable for Card {
 pub struct CardContents {
     pub name: String,
     pub desc: String,
+    pub checklists: Vec<Checklist>,
 }
 
 impl FromStr for CardContents {
@@ -93,11 +156,37 @@ impl FromStr for CardContents {
     ///     trello::CardContents {
     ///         name: String::from("Hello World"),
     ///         desc: String::from("This is my card"),
+    ///         checklists: vec![],
     ///     },
     /// );
     /// # Ok(())
     /// # }
     /// ```
+    /// Checklists are written after the description as markdown task lists, each
+    /// under a "Checklist: <name>" header. Custom fields are displayed under a
+    /// "Custom Fields" header but are read only, so their contents are ignored.
+    /// ```
+    /// # fn main() -> Result<(), trello::TrelloError> {
+    /// use trello::{CheckItem, CheckItemState, Checklist};
+    ///
+    /// let buffer = "Hello World\n===\nThis is my card\n\nChecklist: Todo\n---\n- [x] milk\n- [ ] eggs";
+    /// let card_contents: trello::CardContents = buffer.parse()?;
+    ///
+    /// assert_eq!(card_contents.desc, "This is my card");
+    /// assert_eq!(
+    ///     card_contents.checklists,
+    ///     vec![Checklist::new(
+    ///         "",
+    ///         "Todo",
+    ///         vec![
+    ///             CheckItem::new("", "milk", CheckItemState::Complete),
+    ///             CheckItem::new("", "eggs", CheckItemState::Incomplete),
+    ///         ],
+    ///     )],
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
     /// Invalid data will result in an appropriate error being returned.
     fn from_str(value: &str) -> Result<CardContents> {
         // this is guaranteed to give at least one result
@@ -129,13 +218,105 @@ impl FromStr for CardContents {
         }
 
         let name = name.join("\n");
-        // The rest of the contents is assumed to be the description
-        let desc = contents.join("\n");
 
-        Ok(CardContents { name, desc })
+        // The description runs until the first section header (if any)
+        let sections_start = (0..contents.len())
+            .find(|&i| {
+                is_checklist_header(&contents[i..]) || is_custom_fields_header(&contents[i..])
+            })
+            .unwrap_or(contents.len());
+        let checklists = parse_checklists(&contents[sections_start..])?;
+
+        let desc = contents[..sections_start].join("\n");
+        let desc = match sections_start == contents.len() {
+            // remove the blank line used to separate the description from the sections
+            false => desc.trim_end_matches('\n').to_string(),
+            true => desc,
+        };
+
+        Ok(CardContents {
+            name,
+            desc,
+            checklists,
+        })
+    }
+}
+
+const CHECKLIST_PREFIX: &str = "Checklist: ";
+const CUSTOM_FIELDS_HEADER: &str = "Custom Fields";
+
+fn is_custom_fields_header(lines: &[&str]) -> bool {
+    match lines {
+        [title, border, ..] => {
+            *title == CUSTOM_FIELDS_HEADER && !border.is_empty() && border.chars().all(|c| c == '-')
+        }
+        _ => false,
     }
 }
 
+fn is_checklist_header(lines: &[&str]) -> bool {
+    match lines {
+        [title, border, ..] => {
+            title.starts_with(CHECKLIST_PREFIX)
+                && !border.is_empty()
+                && border.chars().all(|c| c == '-')
+        }
+        _ => false,
+    }
+}
+
+fn parse_checklists(mut lines: &[&str]) -> Result<Vec<Checklist>> {
+    let mut checklists: Vec<Checklist> = vec![];
+
+    let mut in_custom_fields = false;
+
+    while !lines.is_empty() {
+        if is_custom_fields_header(lines) {
+            in_custom_fields = true;
+            lines = &lines[2..];
+            continue;
+        }
+
+        if is_checklist_header(lines) {
+            in_custom_fields = false;
+            let name = &lines[0][CHECKLIST_PREFIX.len()..];
+            checklists.push(Checklist::new("", name.trim(), vec![]));
+            lines = &lines[2..];
+            continue;
+        }
+
+        let line = lines[0].trim();
+        lines = &lines[1..];
+
+        if line.is_empty() || in_custom_fields {
+            continue;
+        }
+
+        let item = if let Some(name) = line.strip_prefix("- [ ]") {
+            CheckItem::new("", name.trim(), CheckItemState::Incomplete)
+        } else if let Some(name) = line
+            .strip_prefix("- [x]")
+            .or_else(|| line.strip_prefix("- [X]"))
+        {
+            CheckItem::new("", name.trim(), CheckItemState::Complete)
+        } else {
+            return Err(TrelloError::CardParse(format!(
+                "Invalid checklist item '{}'. Items must start with '- [ ]' or '- [x]'",
+                line
+            )));
+        };
+
+        // parse_checklists is only called from the first section header onwards
+        checklists
+            .last_mut()
+            .expect("Checklist items found before a checklist header")
+            .check_items
+            .push(item);
+    }
+
+    Ok(checklists)
+}
+
 impl Card {
     pub fn new(
         id: &str,
@@ -152,7 +333,15 @@ impl Card {
             url: String::from(url),
             labels,
             due,
+            due_complete: false,
             closed: false,
+            checklists: None,
+            id_members: vec![],
+            members: None,
+            id_board: String::new(),
+            id_short: None,
+            custom_field_items: None,
+            custom_fields: None,
         }
     }
 
@@ -161,7 +350,7 @@ impl Card {
             .config
             .get_trello_url(&format!("/1/cards/{}", card_id), &[])?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 
     pub fn create(client: &TrelloClient, list_id: &str, card: &Card) -> Result<Card> {
@@ -173,13 +362,7 @@ impl Card {
             ("idList", list_id),
         ];
 
-        Ok(client
-            .client
-            .post(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
     }
 
     pub fn open(client: &TrelloClient, card_id: &str) -> Result<Card> {
@@ -189,13 +372,7 @@ impl Card {
 
         let params = [("closed", "false")];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     pub fn update(client: &TrelloClient, card: &Card) -> Result<Card> {
@@ -209,13 +386,7 @@ impl Card {
             ("closed", &card.closed.to_string()),
         ];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     // Moves a card to the list with the specified id
@@ -226,12 +397,128 @@ impl Card {
 
         let params = [("idList", list_id)];
 
-        client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?;
+        client.send(client.client.put(url).form(&params))?;
+
+        Ok(())
+    }
+
+    /// Moves a card to a list of another board
+    pub fn change_board(
+        client: &TrelloClient,
+        card_id: &str,
+        board_id: &str,
+        list_id: &str,
+    ) -> Result<()> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/", card_id), &[])?;
+
+        let params = [("idBoard", board_id), ("idList", list_id)];
+
+        client.send(client.client.put(url).form(&params))?;
+
+        Ok(())
+    }
+
+    /// Renders the due date of the card (if any) in local time. The date is coloured
+    /// to show whether the card is complete, overdue or due within the next day.
+    fn render_due(&self, now: DateTime<Utc>) -> Option<String> {
+        let due = self.due?;
+        let text = format!(
+            "[Due {}]",
+            due.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        );
+
+        let text = if self.due_complete {
+            text.green()
+        } else if due < now {
+            text.red()
+        } else if due < now + Duration::days(1) {
+            text.yellow()
+        } else {
+            text.normal()
+        };
+        Some(text.to_string())
+    }
+
+    /// Retrieves the custom field definitions of the board the card belongs to, along
+    /// with the custom field values of the card itself. No values are retrieved if the
+    /// board has no custom fields.
+    pub fn retrieve_custom_fields(&mut self, client: &TrelloClient) -> Result<()> {
+        if self.id_board.is_empty() {
+            debug!("Card {} has no board id, skipping custom fields", self.id);
+            return Ok(());
+        }
+
+        let fields = CustomField::get_all(client, &self.id_board)?;
+        if !fields.is_empty() && self.custom_field_items.is_none() {
+            self.custom_field_items = Some(CustomFieldItem::get_all(client, &self.id)?);
+        }
+        self.custom_fields = Some(fields);
+
+        Ok(())
+    }
+
+    /// Sets the due date of the card. Passing None will clear the due date.
+    pub fn set_due(
+        client: &TrelloClient,
+        card_id: &str,
+        due: Option<DateTime<Utc>>,
+    ) -> Result<Card> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/", card_id), &[])?;
+
+        let due = match due {
+            Some(d) => d.to_rfc3339(),
+            None => String::from("null"),
+        };
+        let params = [("due", due)];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    pub fn set_due_complete(client: &TrelloClient, card_id: &str, complete: bool) -> Result<Card> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/", card_id), &[])?;
+
+        let params = [("dueComplete", complete.to_string())];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    /// Fills in the members of the card from the given board members,
+    /// based on the member ids assigned to the card.
+    pub fn populate_members(&mut self, board_members: &[Member]) {
+        self.members = Some(
+            board_members
+                .iter()
+                .filter(|m| self.id_members.contains(&m.id))
+                .cloned()
+                .collect(),
+        );
+    }
+
+    pub fn add_member(client: &TrelloClient, card_id: &str, member_id: &str) -> Result<()> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/idMembers", card_id), &[])?;
+
+        let params = [("value", member_id)];
+
+        client.send(client.client.post(url).form(&params))?;
+
+        Ok(())
+    }
+
+    pub fn remove_member(client: &TrelloClient, card_id: &str, member_id: &str) -> Result<()> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/idMembers/{}", card_id, member_id),
+            &[],
+        )?;
+
+        client.send(client.client.delete(url))?;
 
         Ok(())
     }
@@ -241,6 +528,6 @@ impl Card {
             &format!("/1/lists/{}/cards/", list_id),
             &[("fields", &Card::get_fields().join(","))],
         )?;
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 }
@@ -0,0 +1,301 @@
+use crate::transport::Transport;
+use crate::trello_error::TrelloError;
+
+use base64::prelude::{BASE64_STANDARD, Engine};
+use reqwest::blocking::{Request, Response};
+use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, TRANSFER_ENCODING};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+const CASSETTE_VERSION: u32 = 1;
+const REDACTED: &str = "REDACTED";
+/// Placeholder for the randomly generated boundary of multipart bodies, so that
+/// uploads can be matched against previous recordings
+const BOUNDARY: &str = "BOUNDARY";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Requests are sent to Trello and written to the cassette along with their responses
+    Record,
+    /// Responses are served from the cassette without sending any requests
+    Replay,
+}
+
+/// Configures a TrelloClient to record its requests to, or replay them from, a cassette
+/// file. Can also be enabled by setting the `TRO_RECORD` or `TRO_REPLAY` environment
+/// variables to the path of the cassette.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct CassetteConfig {
+    pub mode: CassetteMode,
+    pub path: PathBuf,
+}
+
+impl CassetteConfig {
+    pub fn new(mode: CassetteMode, path: &Path) -> CassetteConfig {
+        CassetteConfig {
+            mode,
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn from_env() -> Option<CassetteConfig> {
+        if let Some(path) = env::var_os("TRO_RECORD") {
+            Some(CassetteConfig::new(CassetteMode::Record, Path::new(&path)))
+        } else {
+            env::var_os("TRO_REPLAY")
+                .map(|path| CassetteConfig::new(CassetteMode::Replay, Path::new(&path)))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    /// Bodies which are not valid UTF-8, such as downloaded attachments, are
+    /// stored base64 encoded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A recorded session against Trello. Bodies are stored as text, or as base64
+/// when they are binary.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Cassette {
+            version: CASSETTE_VERSION,
+            interactions: vec![],
+        }
+    }
+}
+
+impl Cassette {
+    pub fn load(path: &Path) -> Result<Cassette> {
+        let contents = fs::read_to_string(path)?;
+        let cassette: Cassette = serde_json::from_str(&contents).map_err(|e| {
+            TrelloError::Cassette(format!("Invalid cassette {}: {}", path.display(), e))
+        })?;
+
+        if cassette.version != CASSETTE_VERSION {
+            return Err(TrelloError::Cassette(format!(
+                "Unsupported cassette version {} in {}",
+                cassette.version,
+                path.display()
+            )));
+        }
+        Ok(cassette)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| TrelloError::Cassette(format!("Unable to serialize cassette: {}", e)))?;
+
+        Ok(fs::write(path, contents)?)
+    }
+}
+
+fn headers_to_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = match name == AUTHORIZATION {
+                true => String::from(REDACTED),
+                false => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// Captures the parts of a request used for matching. The body is buffered so that
+/// the request can still be sent afterwards.
+fn record_request(request: &mut Request) -> Result<RecordedRequest> {
+    let boundary = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split("boundary=").nth(1))
+        .map(String::from);
+
+    let body = match request.body_mut() {
+        Some(body) => {
+            let body = String::from_utf8_lossy(body.buffer()?).into_owned();
+            Some(match &boundary {
+                Some(boundary) => body.replace(boundary.as_str(), BOUNDARY),
+                None => body,
+            })
+        }
+        None => None,
+    };
+
+    let mut headers = headers_to_map(request.headers());
+    if let (Some(boundary), Some(content_type)) = (boundary, headers.get_mut(CONTENT_TYPE.as_str()))
+    {
+        *content_type = content_type.replace(&boundary, BOUNDARY);
+    }
+
+    Ok(RecordedRequest {
+        method: request.method().to_string(),
+        url: request.url().to_string(),
+        headers,
+        body,
+    })
+}
+
+/// Reads the whole body of a response so that it can be stored
+pub(crate) fn record_response(response: Response) -> Result<RecordedResponse> {
+    // the body is stored decoded, so headers describing its encoding on the wire are dropped
+    let mut headers = headers_to_map(response.headers());
+    headers.remove(CONTENT_LENGTH.as_str());
+    headers.remove(TRANSFER_ENCODING.as_str());
+
+    let status = response.status().as_u16();
+    let (body, base64) = match String::from_utf8(response.bytes()?.to_vec()) {
+        Ok(body) => (body, false),
+        Err(e) => (BASE64_STANDARD.encode(e.into_bytes()), true),
+    };
+
+    Ok(RecordedResponse {
+        status,
+        headers,
+        body,
+        base64,
+    })
+}
+
+pub(crate) fn to_response(recorded: &RecordedResponse) -> Result<Response> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name, value);
+    }
+    let body = match recorded.base64 {
+        true => BASE64_STANDARD.decode(&recorded.body).map_err(|e| {
+            TrelloError::Cassette(format!("Invalid base64 body of recorded response: {}", e))
+        })?,
+        false => recorded.body.clone().into_bytes(),
+    };
+    let response = builder
+        .body(body)
+        .map_err(|e| TrelloError::Cassette(format!("Invalid recorded response: {}", e)))?;
+
+    Ok(Response::from(response))
+}
+
+/// Transport which sends requests with another Transport and writes every request
+/// and response to a cassette file. The Authorization header is redacted.
+#[derive(Debug)]
+pub struct RecordingTransport {
+    inner: Box<dyn Transport>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Box<dyn Transport>, path: &Path) -> RecordingTransport {
+        RecordingTransport {
+            inner,
+            path: path.to_path_buf(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn execute(&self, mut request: Request) -> Result<Response> {
+        let recorded_request = record_request(&mut request)?;
+        let recorded_response = record_response(self.inner.execute(request)?)?;
+        let response = to_response(&recorded_response)?;
+
+        let mut cassette = self.cassette.lock().expect("Cassette lock was poisoned");
+        cassette.interactions.push(Interaction {
+            request: recorded_request,
+            response: recorded_response,
+        });
+        // saved after every interaction so that nothing is lost if the program exits early
+        cassette.save(&self.path)?;
+
+        Ok(response)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+}
+
+/// Transport which serves responses from a cassette file without a network.
+/// Each recorded interaction is served at most once, in the order it was recorded.
+/// Requests which do not match any remaining interaction fail with a Cassette error.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    path: PathBuf,
+    /// Interactions which have not been served yet. Loaded on the first request.
+    remaining: Mutex<Option<Vec<Interaction>>>,
+}
+
+impl ReplayTransport {
+    pub fn new(path: &Path) -> ReplayTransport {
+        ReplayTransport {
+            path: path.to_path_buf(),
+            remaining: Mutex::new(None),
+        }
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn execute(&self, mut request: Request) -> Result<Response> {
+        let recorded = record_request(&mut request)?;
+
+        let mut remaining = self.remaining.lock().expect("Cassette lock was poisoned");
+        if remaining.is_none() {
+            *remaining = Some(Cassette::load(&self.path)?.interactions);
+        }
+        let interactions = remaining.as_mut().expect("Cassette was not loaded");
+
+        let index = interactions
+            .iter()
+            .position(|i| {
+                i.request.method == recorded.method
+                    && i.request.url == recorded.url
+                    && i.request.body == recorded.body
+            })
+            .ok_or_else(|| {
+                TrelloError::Cassette(format!(
+                    "No recorded interaction in {} matches {} {}",
+                    self.path.display(),
+                    recorded.method,
+                    recorded.url
+                ))
+            })?;
+
+        let interaction = interactions.remove(index);
+        to_response(&interaction.response)
+    }
+}
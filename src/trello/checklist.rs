@@ -0,0 +1,349 @@
+use crate::client::TrelloClient;
+use crate::formatting::header;
+use crate::trello_error::TrelloError;
+use crate::trello_object::{Renderable, TrelloObject};
+
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+// https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#checklist-object
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Checklist {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub check_items: Vec<CheckItem>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckItemState {
+    Complete,
+    Incomplete,
+}
+
+impl CheckItemState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckItemState::Complete => "complete",
+            CheckItemState::Incomplete => "incomplete",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckItem {
+    pub id: String,
+    pub name: String,
+    pub state: CheckItemState,
+}
+
+impl TrelloObject for Checklist {
+    fn get_type() -> String {
+        String::from("Checklist")
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "name"]
+    }
+}
+
+impl TrelloObject for CheckItem {
+    fn get_type() -> String {
+        String::from("CheckItem")
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "name", "state"]
+    }
+}
+
+impl Renderable for Checklist {
+    fn render(&self, headers: bool) -> String {
+        let mut result = match headers {
+            true => vec![header(&format!("Checklist: {}", self.name), "-")],
+            false => vec![],
+        };
+        for item in &self.check_items {
+            result.push(item.simple_render());
+        }
+        result.join("\n")
+    }
+
+    fn simple_render(&self) -> String {
+        let complete = self
+            .check_items
+            .iter()
+            .filter(|i| i.state == CheckItemState::Complete)
+            .count();
+
+        format!("{} ({}/{})", self.name, complete, self.check_items.len())
+    }
+}
+
+impl Renderable for CheckItem {
+    fn render(&self, _: bool) -> String {
+        self.simple_render()
+    }
+
+    fn simple_render(&self) -> String {
+        match self.state {
+            CheckItemState::Complete => format!("- [x] {}", self.name),
+            CheckItemState::Incomplete => format!("- [ ] {}", self.name),
+        }
+    }
+}
+
+impl Checklist {
+    pub fn new(id: &str, name: &str, check_items: Vec<CheckItem>) -> Checklist {
+        Checklist {
+            id: String::from(id),
+            name: String::from(name),
+            check_items,
+        }
+    }
+
+    pub fn get_all(client: &TrelloClient, card_id: &str) -> Result<Vec<Checklist>> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/checklists", card_id),
+            &[
+                ("fields", &Checklist::get_fields().join(",")),
+                ("checkItem_fields", &CheckItem::get_fields().join(",")),
+            ],
+        )?;
+
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+
+    pub fn create(client: &TrelloClient, card_id: &str, name: &str) -> Result<Checklist> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/checklists", card_id), &[])?;
+
+        let params = [("name", name)];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
+
+    pub fn rename(client: &TrelloClient, checklist_id: &str, name: &str) -> Result<Checklist> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/checklists/{}", checklist_id), &[])?;
+
+        let params = [("name", name)];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    pub fn delete(client: &TrelloClient, checklist_id: &str) -> Result<()> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/checklists/{}", checklist_id), &[])?;
+
+        client.send(client.client.delete(url))?;
+
+        Ok(())
+    }
+
+    /// Updates the checklists of a card so that they match the `desired` checklists.
+    /// Checklists and items are paired up by name first, and then by position amongst
+    /// whatever is left over, so that editing the name of an item renames it rather
+    /// than re-creating it. Ids of the `desired` checklists are ignored.
+    ///
+    /// The up to date checklists of the card are returned.
+    pub fn sync(
+        client: &TrelloClient,
+        card_id: &str,
+        current: &[Checklist],
+        desired: &[Checklist],
+    ) -> Result<Vec<Checklist>> {
+        for (cur, des) in pair_by_name(current, desired) {
+            match (cur, des) {
+                (Some(cur), Some(des)) => {
+                    if cur.name != des.name {
+                        Checklist::rename(client, &cur.id, &des.name)?;
+                    }
+                    sync_items(client, card_id, &cur.id, &cur.check_items, &des.check_items)?;
+                }
+                (None, Some(des)) => {
+                    let checklist = Checklist::create(client, card_id, &des.name)?;
+                    sync_items(client, card_id, &checklist.id, &[], &des.check_items)?;
+                }
+                (Some(cur), None) => Checklist::delete(client, &cur.id)?,
+                (None, None) => unreachable!(),
+            }
+        }
+
+        Checklist::get_all(client, card_id)
+    }
+
+    /// Returns true if the names, items or item states of the two sets
+    /// of checklists differ. Ids are not taken into account.
+    pub fn differs(current: &[Checklist], desired: &[Checklist]) -> bool {
+        let summary = |checklists: &[Checklist]| {
+            checklists
+                .iter()
+                .map(|c| {
+                    (
+                        c.name.clone(),
+                        c.check_items
+                            .iter()
+                            .map(|i| (i.name.clone(), i.state))
+                            .collect::<Vec<(String, CheckItemState)>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        summary(current) != summary(desired)
+    }
+}
+
+impl CheckItem {
+    pub fn new(id: &str, name: &str, state: CheckItemState) -> CheckItem {
+        CheckItem {
+            id: String::from(id),
+            name: String::from(name),
+            state,
+        }
+    }
+
+    pub fn create(
+        client: &TrelloClient,
+        checklist_id: &str,
+        name: &str,
+        state: CheckItemState,
+    ) -> Result<CheckItem> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/checklists/{}/checkItems", checklist_id), &[])?;
+
+        let checked = (state == CheckItemState::Complete).to_string();
+        let params = [("name", name), ("checked", &checked)];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
+
+    pub fn update(client: &TrelloClient, card_id: &str, item: &CheckItem) -> Result<CheckItem> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/checkItem/{}", card_id, item.id), &[])?;
+
+        let params = [("name", item.name.as_str()), ("state", item.state.as_str())];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    pub fn check(client: &TrelloClient, card_id: &str, item_id: &str) -> Result<CheckItem> {
+        CheckItem::set_state(client, card_id, item_id, CheckItemState::Complete)
+    }
+
+    pub fn uncheck(client: &TrelloClient, card_id: &str, item_id: &str) -> Result<CheckItem> {
+        CheckItem::set_state(client, card_id, item_id, CheckItemState::Incomplete)
+    }
+
+    fn set_state(
+        client: &TrelloClient,
+        card_id: &str,
+        item_id: &str,
+        state: CheckItemState,
+    ) -> Result<CheckItem> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/checkItem/{}", card_id, item_id), &[])?;
+
+        let params = [("state", state.as_str())];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    pub fn delete(client: &TrelloClient, checklist_id: &str, item_id: &str) -> Result<()> {
+        let url = client.config.get_trello_url(
+            &format!("/1/checklists/{}/checkItems/{}", checklist_id, item_id),
+            &[],
+        )?;
+
+        client.send(client.client.delete(url))?;
+
+        Ok(())
+    }
+}
+
+fn sync_items(
+    client: &TrelloClient,
+    card_id: &str,
+    checklist_id: &str,
+    current: &[CheckItem],
+    desired: &[CheckItem],
+) -> Result<()> {
+    for (cur, des) in pair_by_name(current, desired) {
+        match (cur, des) {
+            (Some(cur), Some(des)) => {
+                if cur.name != des.name || cur.state != des.state {
+                    let mut item = des.clone();
+                    item.id = cur.id.clone();
+                    CheckItem::update(client, card_id, &item)?;
+                }
+            }
+            (None, Some(des)) => {
+                CheckItem::create(client, checklist_id, &des.name, des.state)?;
+            }
+            (Some(cur), None) => CheckItem::delete(client, checklist_id, &cur.id)?,
+            (None, None) => unreachable!(),
+        }
+    }
+    Ok(())
+}
+
+/// Pairs up current and desired objects by exact name, and then by position amongst
+/// the objects which were left unmatched. Any object without a counterpart is
+/// paired with None.
+fn pair_by_name<'a, T: TrelloObject>(
+    current: &'a [T],
+    desired: &'a [T],
+) -> Vec<(Option<&'a T>, Option<&'a T>)> {
+    let mut matched = vec![false; current.len()];
+    let mut pairs = vec![];
+    let mut unmatched_desired = vec![];
+
+    for des in desired {
+        let found = current
+            .iter()
+            .enumerate()
+            .position(|(i, cur)| !matched[i] && cur.get_name() == des.get_name());
+
+        match found {
+            Some(i) => {
+                matched[i] = true;
+                pairs.push((Some(&current[i]), Some(des)));
+            }
+            None => unmatched_desired.push(des),
+        }
+    }
+
+    let mut unmatched_current = current
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !matched[*i])
+        .map(|(_, cur)| cur);
+
+    for des in unmatched_desired {
+        pairs.push((unmatched_current.next(), Some(des)));
+    }
+    for cur in unmatched_current {
+        pairs.push((Some(cur), None));
+    }
+
+    pairs
+}
@@ -1,42 +1,348 @@
+use crate::cache::{CacheConfig, CachingTransport};
+use crate::cassette::{CassetteConfig, CassetteMode, RecordingTransport, ReplayTransport};
+use crate::profiles::Profiles;
+use crate::transport::{HttpTransport, Transport};
+use crate::trello_error::TrelloError;
+
+use reqwest::Method;
+use reqwest::blocking::{Client, RequestBuilder, Response};
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::env;
 use std::error::Error;
-use std::fs;
 use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ClientConfig {
     #[serde(default = "ClientConfig::default_host")]
     pub host: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub token: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub key: String,
+    /// Command whose output is used as the token, e.g. `pass trello/token`, so that
+    /// the token does not have to be stored in the configuration file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+    /// Command whose output is used as the API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_command: Option<String>,
+    /// Minimum number of milliseconds between consecutive requests. Trello allows
+    /// 100 requests per 10 seconds for each token, so a value of 100 keeps a client
+    /// under that budget. Throttling is disabled when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throttle_ms: Option<u64>,
+    /// Records requests to, or replays them from, a cassette file. Falls back to
+    /// the `TRO_RECORD` and `TRO_REPLAY` environment variables when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cassette: Option<CassetteConfig>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Match names of boards, lists and cards fuzzily (as fzf does) rather than
+    /// as regular expressions
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fuzzy: bool,
+    /// Caches boards along with their lists, labels and members on disk.
+    /// Enabled by default when loading a configuration file.
+    #[serde(
+        default = "ClientConfig::default_cache",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cache: Option<CacheConfig>,
+    /// Name of the profile the configuration was loaded from
+    #[serde(skip)]
+    pub profile: Option<String>,
+}
+
+/// Determines how requests are retried when Trello rate limits them or fails with
+/// a transient error. Rate limited requests are retried regardless of their method
+/// because Trello rejects them without processing them. Server errors and
+/// connection failures are only retried for idempotent requests.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total number of attempts made for a request, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry. The delay doubles on every subsequent retry
+    pub base_delay_ms: u64,
+    /// Upper bound for the delay between two attempts
+    pub max_delay_ms: u64,
+    /// Wait for the number of seconds requested by Trello in the Retry-After header.
+    /// Requests are not retried when this exceeds the maximum delay.
+    pub honour_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            honour_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries requests
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    fn should_retry(&self, error: &TrelloError, idempotent: bool) -> bool {
+        match error {
+            TrelloError::RateLimited { .. } => true,
+            TrelloError::Api { status, .. } => idempotent && *status >= 500,
+            TrelloError::Reqwest(e) => idempotent && (e.is_timeout() || e.is_connect()),
+            _ => false,
+        }
+    }
+
+    /// Delay to wait before making the given retry attempt (starting from 1). None
+    /// when Trello asks to wait for longer than the maximum delay, in which case the
+    /// request is not retried.
+    fn delay(&self, retry: u32, error: &TrelloError) -> Option<Duration> {
+        if let TrelloError::RateLimited {
+            retry_after: Some(seconds),
+        } = error
+            && self.honour_retry_after
+        {
+            let delay = Duration::from_secs(*seconds);
+            return match delay <= Duration::from_millis(self.max_delay_ms) {
+                true => Some(delay),
+                false => None,
+            };
+        }
+
+        let exponent = retry.saturating_sub(1).min(16);
+        let delay = self.base_delay_ms.saturating_mul(1 << exponent);
+        Some(Duration::from_millis(delay.min(self.max_delay_ms)))
+    }
 }
 
 #[derive(Debug)]
 pub struct TrelloClient {
     pub config: ClientConfig,
-    pub client: reqwest::blocking::Client,
+    pub client: Client,
+    authorization: HeaderValue,
+    transport: Box<dyn Transport>,
+    last_request: Mutex<Option<Instant>>,
+    request_count: AtomicUsize,
 }
 
 impl TrelloClient {
     pub fn new(config: ClientConfig) -> Self {
-        let auth_value = HeaderValue::from_str(&format!(
+        let client = TrelloClient::build_http_client(&config);
+        let transport = HttpTransport::new(client.clone());
+
+        TrelloClient::create(config, client, Box::new(transport))
+    }
+
+    /// Creates a client which executes its requests with the given Transport
+    /// instead of sending them over the network.
+    pub fn with_transport<T: Transport + 'static>(config: ClientConfig, transport: T) -> Self {
+        let client = TrelloClient::build_http_client(&config);
+
+        TrelloClient::create(config, client, Box::new(transport))
+    }
+
+    fn create(config: ClientConfig, client: Client, transport: Box<dyn Transport>) -> Self {
+        let cassette = config.cassette.clone().or_else(CassetteConfig::from_env);
+        // cached responses would be missing from recorded cassettes
+        let cache = match (&config.cache, &cassette) {
+            (Some(cache), None) if cache.ttl_secs > 0 => {
+                match cache.dir.clone().map_or_else(ClientConfig::cache_dir, Ok) {
+                    // each profile has its own cache
+                    Ok(dir) => Some((
+                        cache,
+                        CacheConfig::path(
+                            &dir.join(config.profile.as_deref().unwrap_or_default()),
+                            &config.host,
+                            &config.key,
+                            &config.token,
+                        ),
+                    )),
+                    Err(e) => {
+                        debug!("Unable to cache responses: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let transport: Box<dyn Transport> = match cassette {
+            Some(cassette) if cassette.mode == CassetteMode::Record => {
+                debug!("Recording requests to {:?}", cassette.path);
+                Box::new(RecordingTransport::new(transport, &cassette.path))
+            }
+            Some(cassette) => {
+                debug!("Replaying requests from {:?}", cassette.path);
+                Box::new(ReplayTransport::new(&cassette.path))
+            }
+            None => transport,
+        };
+        let transport: Box<dyn Transport> = match cache {
+            Some((cache, path)) => {
+                debug!("Caching responses in {:?}", path);
+                Box::new(CachingTransport::new(transport, &path, cache))
+            }
+            None => transport,
+        };
+
+        TrelloClient {
+            authorization: TrelloClient::authorization(&config),
+            config,
+            client,
+            transport,
+            last_request: Mutex::new(None),
+            request_count: AtomicUsize::new(0),
+        }
+    }
+
+    fn authorization(config: &ClientConfig) -> HeaderValue {
+        let mut value = HeaderValue::from_str(&format!(
             "OAuth oauth_consumer_key=\"{}\", oauth_token=\"{}\"",
             config.key, config.token
         ))
         .expect("API key and token must contain only visible ASCII characters");
+        value.set_sensitive(true);
+
+        value
+    }
 
+    fn build_http_client(config: &ClientConfig) -> Client {
         let mut headers = HeaderMap::new();
-        headers.insert(AUTHORIZATION, auth_value);
+        headers.insert(AUTHORIZATION, TrelloClient::authorization(config));
 
-        TrelloClient {
-            config,
-            client: reqwest::blocking::Client::builder()
-                .default_headers(headers)
-                .build()
-                .expect("Failed to build HTTP client"),
+        Client::builder()
+            .default_headers(headers)
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    /// Whether the client is unable to modify objects, e.g. when reading from an export
+    pub fn is_read_only(&self) -> bool {
+        self.transport.is_read_only()
+    }
+
+    /// Number of requests sent to Trello by the client so far, counting every retry.
+    /// Requests answered from the cache are not counted.
+    pub fn request_count(&self) -> usize {
+        self.request_count.load(Ordering::Relaxed) - self.transport.cache_hits()
+    }
+
+    /// Whether any object was read from the cache rather than from Trello,
+    /// in which case it may be out of date
+    pub fn served_from_cache(&self) -> bool {
+        self.transport.served_from_cache()
+    }
+
+    /// Discards all cached objects
+    pub fn invalidate_cache(&self) {
+        self.transport.invalidate_cache()
+    }
+
+    /// Sends the given request to Trello. Unsuccessful responses are converted
+    /// into the TrelloError matching their HTTP status. Failed requests are
+    /// retried according to the configured RetryPolicy.
+    pub fn send(&self, request: RequestBuilder) -> Result<Response, TrelloError> {
+        let policy = &self.config.retry;
+        let mut request = request.build()?;
+        // set explicitly (rather than relying on the default headers of the client)
+        // so that the header is visible to the Transport
+        request
+            .headers_mut()
+            .entry(AUTHORIZATION)
+            .or_insert_with(|| self.authorization.clone());
+        let idempotent = matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE
+        );
+
+        let mut attempt = 1;
+        loop {
+            // Requests with streaming bodies (such as file uploads) cannot be cloned
+            // and are therefore never retried
+            let next_request = match attempt < policy.max_attempts {
+                true => request.try_clone(),
+                false => None,
+            };
+
+            self.throttle();
+            self.request_count.fetch_add(1, Ordering::Relaxed);
+            let error = match self.transport.execute(request) {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => TrelloError::from_response(response),
+                Err(e) => e,
+            };
+
+            request = match next_request {
+                Some(r) if policy.should_retry(&error, idempotent) => r,
+                _ => return Err(error),
+            };
+
+            let delay = match policy.delay(attempt, &error) {
+                Some(delay) => delay,
+                None => {
+                    debug!("Not retrying as {} exceeds the maximum delay", error);
+                    return Err(error);
+                }
+            };
+            debug!(
+                "Attempt {} failed ({}), retrying in {:?}",
+                attempt, error, delay
+            );
+            thread::sleep(delay);
+
+            attempt += 1;
         }
     }
+
+    /// Waits until at least `throttle_ms` have passed since the previous request
+    fn throttle(&self) {
+        let mut last_request = self
+            .last_request
+            .lock()
+            .expect("Throttle lock was poisoned");
+
+        if let (Some(throttle_ms), Some(last)) = (self.config.throttle_ms, *last_request) {
+            let interval = Duration::from_millis(throttle_ms);
+            let elapsed = last.elapsed();
+            if elapsed < interval {
+                trace!("Throttling request for {:?}", interval - elapsed);
+                thread::sleep(interval - elapsed);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+}
+
+/// Runs a credential helper with the shell and returns the first line of its output
+fn run_credential_command(command: &str) -> Result<String, Box<dyn Error>> {
+    let (shell, flag) = match cfg!(windows) {
+        true => ("cmd", "/C"),
+        false => ("sh", "-c"),
+    };
+    let output = Command::new(shell)
+        .args([flag, command])
+        .stderr(Stdio::inherit())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!("command failed with {}", output.status).into());
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
 }
 
 impl ClientConfig {
@@ -45,6 +351,14 @@ impl ClientConfig {
             host: String::from(host),
             token: String::from(token),
             key: String::from(key),
+            throttle_ms: None,
+            cassette: None,
+            retry: RetryPolicy::default(),
+            fuzzy: false,
+            cache: None,
+            profile: None,
+            token_command: None,
+            key_command: None,
         }
     }
 
@@ -55,6 +369,13 @@ impl ClientConfig {
         Ok(config_path)
     }
 
+    fn cache_dir() -> Result<PathBuf, Box<dyn Error>> {
+        let mut cache_path = Self::config_dir()?;
+        cache_path.push("cache");
+
+        Ok(cache_path)
+    }
+
     fn config_path() -> Result<PathBuf, Box<dyn Error>> {
         let mut config_path = Self::config_dir()?;
         config_path.push("config.toml");
@@ -62,39 +383,102 @@ impl ClientConfig {
         Ok(config_path)
     }
 
+    /// Saves the configuration as its profile (or the default profile) in the
+    /// configuration file, keeping any other profiles stored there. The key and
+    /// token are left out when they are read from a command.
     pub fn save_config(&self) -> Result<(), Box<dyn Error>> {
-        fs::create_dir_all(Self::config_dir()?)?;
-
         let config_path = Self::config_path()?;
-        debug!("Saving configuration to {:?}", config_path);
+        let mut profiles = Profiles::load(&config_path)?;
+        let name = match &self.profile {
+            Some(name) => name.clone(),
+            None => profiles.resolve(None)?,
+        };
+
+        let mut config = self.clone();
+        if config.key_command.is_some() {
+            config.key.clear();
+        }
+        if config.token_command.is_some() {
+            config.token.clear();
+        }
+        profiles.insert(&name, config)?;
+
+        profiles.save(&config_path)
+    }
 
-        fs::write(config_path, toml::to_string(self)?)?;
+    /// Loads the configuration of the given profile. When no profile is given, the
+    /// profile set with the `TRO_PROFILE` environment variable or the default
+    /// profile is loaded.
+    ///
+    /// The API key and token are taken from the `TRO_KEY` and `TRO_TOKEN` environment
+    /// variables when set, or otherwise from the output of `key_command` and
+    /// `token_command`. No configuration file is needed when both variables are set.
+    pub fn load_config(profile: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let env_credentials = env::var("TRO_KEY").is_ok() && env::var("TRO_TOKEN").is_ok();
+
+        let mut config = match Self::load_profiles()?.take(profile) {
+            Ok(config) => config,
+            Err(e) if env_credentials && profile.is_none() => {
+                debug!("Using credentials from the environment ({})", e);
+                ClientConfig::new(&ClientConfig::default_host(), "", "")
+            }
+            Err(e) => return Err(e),
+        };
+        config.resolve_credentials()?;
+
+        Ok(config)
+    }
+
+    /// Fills in the API key and token from the environment or by running the
+    /// configured commands
+    pub fn resolve_credentials(&mut self) -> Result<(), Box<dyn Error>> {
+        for (name, variable, command, value) in [
+            ("key", "TRO_KEY", &self.key_command, &mut self.key),
+            ("token", "TRO_TOKEN", &self.token_command, &mut self.token),
+        ] {
+            if let Ok(secret) = env::var(variable) {
+                *value = secret;
+            } else if let Some(command) = command {
+                *value = run_credential_command(command)
+                    .map_err(|e| format!("Unable to read {} from '{}': {}", name, command, e))?;
+            }
+            if value.is_empty() {
+                return Err(format!("No {} configured. Please run tro setup", name).into());
+            }
+        }
 
         Ok(())
     }
 
-    pub fn load_config() -> Result<Self, Box<dyn Error>> {
-        let config_path = Self::config_path()?;
-        debug!("Loading configuration from {:?}", config_path);
-        let contents = fs::read_to_string(config_path)?;
+    /// Path of the configuration file, if other users are able to read it
+    pub fn world_readable_config() -> Option<PathBuf> {
+        Self::config_path()
+            .ok()
+            .filter(|path| Profiles::is_world_readable(path))
+    }
 
-        Ok(toml::from_str(&contents)?)
+    pub fn load_profiles() -> Result<Profiles, Box<dyn Error>> {
+        Profiles::load(&Self::config_path()?)
+    }
+
+    pub fn save_profiles(profiles: &Profiles) -> Result<(), Box<dyn Error>> {
+        profiles.save(&Self::config_path()?)
     }
 
     pub fn default_host() -> String {
         String::from("https://api.trello.com")
     }
 
+    pub fn default_cache() -> Option<CacheConfig> {
+        Some(CacheConfig::default())
+    }
+
     /// Gets the resultant URL of the Trello Config given some path and additional
     /// parameters. Authentication is handled via the `Authorization` header on the
     /// HTTP client, not as query parameters.
     /// ```
     /// # fn main() -> Result<(), url::ParseError> {
-    /// let config = trello::ClientConfig {
-    ///     host: String::from("https://api.trello.com"),
-    ///     token: String::from("some-token"),
-    ///     key: String::from("some-key"),
-    /// };
+    /// let config = trello::ClientConfig::new("https://api.trello.com", "some-token", "some-key");
     /// let url = config.get_trello_url("/1/me/boards/", &[])?;
     /// assert_eq!(
     ///     url.to_string(),
@@ -0,0 +1,164 @@
+use crate::client::TrelloClient;
+use crate::formatting::header;
+use crate::member::Member;
+use crate::trello_error::TrelloError;
+use crate::trello_object::{Renderable, TrelloObject};
+
+use chrono::{DateTime, Local, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+/// Maximum number of actions Trello returns in a single request
+const PAGE_LIMIT: usize = 1000;
+
+// Comments are stored by Trello as "commentCard" actions on the card
+// https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#action-object
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub date: DateTime<Utc>,
+    pub data: CommentData,
+    pub member_creator: Member,
+}
+
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentData {
+    pub text: String,
+}
+
+impl TrelloObject for Comment {
+    fn get_type() -> String {
+        String::from("Comment")
+    }
+
+    fn get_name(&self) -> &str {
+        &self.data.text
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "date", "data", "idMemberCreator"]
+    }
+}
+
+impl Renderable for Comment {
+    fn render(&self, headers: bool) -> String {
+        let title = format!(
+            "{} (@{}) {}",
+            self.member_creator.full_name,
+            self.member_creator.username,
+            self.local_date()
+        );
+        match headers {
+            true => [
+                header(&title, "-").bold().to_string(),
+                self.data.text.clone(),
+            ]
+            .join("\n"),
+            false => self.data.text.clone(),
+        }
+    }
+
+    fn simple_render(&self) -> String {
+        let first_line = self.data.text.lines().next().unwrap_or_default();
+
+        format!(
+            "{} {}: {}",
+            self.local_date().dimmed(),
+            self.member_creator.username.green(),
+            first_line
+        )
+    }
+}
+
+impl Comment {
+    pub fn new(id: &str, text: &str, date: DateTime<Utc>, member_creator: Member) -> Comment {
+        Comment {
+            id: String::from(id),
+            date,
+            data: CommentData {
+                text: String::from(text),
+            },
+            member_creator,
+        }
+    }
+
+    fn local_date(&self) -> String {
+        self.date
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    /// Retrieves all comments of a card, newest first. Trello returns at most
+    /// `PAGE_LIMIT` actions per request, so older comments are paged through
+    /// using the id of the oldest comment retrieved so far.
+    pub fn get_all(client: &TrelloClient, card_id: &str) -> Result<Vec<Comment>> {
+        let fields = Comment::get_fields().join(",");
+        let member_fields = Member::get_fields().join(",");
+        let limit = PAGE_LIMIT.to_string();
+
+        let mut comments: Vec<Comment> = vec![];
+        loop {
+            let mut params = vec![
+                ("filter", "commentCard"),
+                ("fields", fields.as_str()),
+                ("memberCreator_fields", member_fields.as_str()),
+                ("limit", limit.as_str()),
+            ];
+            if let Some(oldest) = comments.last() {
+                params.push(("before", &oldest.id));
+            }
+            let url = client
+                .config
+                .get_trello_url(&format!("/1/cards/{}/actions", card_id), &params)?;
+
+            let page: Vec<Comment> = client.send(client.client.get(url))?.json()?;
+            let done = page.len() < PAGE_LIMIT;
+            comments.extend(page);
+            if done {
+                return Ok(comments);
+            }
+        }
+    }
+
+    pub fn create(client: &TrelloClient, card_id: &str, text: &str) -> Result<Comment> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/actions/comments", card_id), &[])?;
+
+        let params = [("text", text)];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
+
+    pub fn update(
+        client: &TrelloClient,
+        card_id: &str,
+        comment_id: &str,
+        text: &str,
+    ) -> Result<Comment> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/actions/{}/comments", card_id, comment_id),
+            &[],
+        )?;
+
+        let params = [("text", text)];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    pub fn delete(client: &TrelloClient, card_id: &str, comment_id: &str) -> Result<()> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/actions/{}/comments", card_id, comment_id),
+            &[],
+        )?;
+
+        client.send(client.client.delete(url))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::client::TrelloClient;
+use crate::trello_error::TrelloError;
+use crate::trello_object::{Renderable, TrelloObject};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+// https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#custom-field-object
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomField {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<CustomFieldOption>,
+}
+
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Checkbox,
+    List,
+}
+
+/// An option of a "list" (dropdown) custom field
+#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldOption {
+    pub id: String,
+    pub value: CustomFieldItemValue,
+}
+
+/// The value of a custom field on a specific card
+// https://developer.atlassian.com/cloud/trello/guides/rest-api/object-definitions/#custom-field-items
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldItem {
+    pub id: String,
+    pub id_custom_field: String,
+    pub value: Option<CustomFieldItemValue>,
+    pub id_value: Option<String>,
+}
+
+/// Raw value of a custom field as returned by Trello. Only the attribute
+/// corresponding to the type of the custom field will be set.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldItemValue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked: Option<String>,
+}
+
+/// A typed value which can be set on a card
+#[derive(Debug, PartialEq, Clone)]
+pub enum CustomFieldValue {
+    Text(String),
+    Number(f64),
+    Date(DateTime<Utc>),
+    Checkbox(bool),
+    /// Id of the selected option of a list custom field
+    Option(String),
+}
+
+impl TrelloObject for CustomField {
+    fn get_type() -> String {
+        String::from("Custom Field")
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "name", "type", "options"]
+    }
+}
+
+impl TrelloObject for CustomFieldOption {
+    fn get_type() -> String {
+        String::from("Option")
+    }
+
+    fn get_name(&self) -> &str {
+        self.value.text.as_deref().unwrap_or_default()
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "value"]
+    }
+}
+
+impl Renderable for CustomFieldOption {
+    fn render(&self, _: bool) -> String {
+        self.simple_render()
+    }
+
+    fn simple_render(&self) -> String {
+        String::from(self.get_name())
+    }
+}
+
+impl CustomField {
+    pub fn new(
+        id: &str,
+        name: &str,
+        field_type: CustomFieldType,
+        options: Vec<CustomFieldOption>,
+    ) -> CustomField {
+        CustomField {
+            id: String::from(id),
+            name: String::from(name),
+            field_type,
+            options,
+        }
+    }
+
+    /// Formats the value of this custom field found in the given items (if any)
+    /// for display. Dates are shown in local time.
+    pub fn format_value(&self, items: &[CustomFieldItem]) -> Option<String> {
+        let item = items.iter().find(|i| i.id_custom_field == self.id)?;
+
+        if self.field_type == CustomFieldType::List {
+            let id_value = item.id_value.as_ref()?;
+            let option = self.options.iter().find(|o| &o.id == id_value)?;
+            return Some(String::from(option.get_name()));
+        }
+
+        let value = item.value.as_ref()?;
+        match self.field_type {
+            CustomFieldType::Text => value.text.clone(),
+            CustomFieldType::Number => value.number.clone(),
+            CustomFieldType::Date => {
+                value
+                    .date
+                    .as_ref()
+                    .map(|d| match DateTime::parse_from_rfc3339(d) {
+                        Ok(d) => d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+                        Err(_) => d.clone(),
+                    })
+            }
+            CustomFieldType::Checkbox => value.checked.as_ref().map(|c| match c.as_str() {
+                "true" => String::from("yes"),
+                _ => String::from("no"),
+            }),
+            CustomFieldType::List => unreachable!(),
+        }
+    }
+
+    pub fn get_all(client: &TrelloClient, board_id: &str) -> Result<Vec<CustomField>> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/boards/{}/customFields", board_id), &[])?;
+
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+}
+
+impl CustomFieldItem {
+    pub fn get_all(client: &TrelloClient, card_id: &str) -> Result<Vec<CustomFieldItem>> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/cards/{}/customFieldItems", card_id), &[])?;
+
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+
+    /// Sets the value of a custom field on a card. Passing None clears the value.
+    pub fn update(
+        client: &TrelloClient,
+        card_id: &str,
+        field: &CustomField,
+        value: Option<&CustomFieldValue>,
+    ) -> Result<()> {
+        let url = client.config.get_trello_url(
+            &format!("/1/cards/{}/customField/{}/item", card_id, field.id),
+            &[],
+        )?;
+
+        let body = match (field.field_type, value) {
+            (CustomFieldType::List, None) => json!({"idValue": ""}),
+            (_, None) => json!({"value": ""}),
+            (_, Some(CustomFieldValue::Text(t))) => json!({"value": {"text": t}}),
+            (_, Some(CustomFieldValue::Number(n))) => {
+                json!({"value": {"number": n.to_string()}})
+            }
+            (_, Some(CustomFieldValue::Date(d))) => json!({"value": {"date": d.to_rfc3339()}}),
+            (_, Some(CustomFieldValue::Checkbox(c))) => {
+                json!({"value": {"checked": c.to_string()}})
+            }
+            (_, Some(CustomFieldValue::Option(id))) => json!({"idValue": id}),
+        };
+
+        client.send(client.client.put(url).json(&body))?;
+
+        Ok(())
+    }
+}
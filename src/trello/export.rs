@@ -0,0 +1,161 @@
+use crate::attachment::Attachment;
+use crate::board::Board;
+use crate::card::Card;
+use crate::checklist::Checklist;
+use crate::client::TrelloClient;
+use crate::comment::Comment;
+use crate::label::Label;
+use crate::list::List;
+use crate::trello_error::TrelloError;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+/// Version of the export format. Bumped whenever a change is made which
+/// older versions of tro would not be able to import.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A snapshot of a board along with everything needed to recreate it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub board: Board,
+    pub labels: Vec<Label>,
+    pub lists: Vec<ListExport>,
+}
+
+/// A list and its open cards, in the order they appear on the board
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListExport {
+    #[serde(flatten)]
+    pub list: List,
+    pub cards: Vec<CardExport>,
+}
+
+/// A card (including its checklists) along with its comments and the
+/// metadata of its attachments. Attachment contents are not exported.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CardExport {
+    #[serde(flatten)]
+    pub card: Card,
+    pub comments: Vec<Comment>,
+    pub attachments: Vec<Attachment>,
+}
+
+impl BoardExport {
+    /// Takes a snapshot of the given board. Nested content of the board is
+    /// retrieved if it has not been already.
+    pub fn create(client: &TrelloClient, board: &Board) -> Result<BoardExport> {
+        let mut board = board.clone();
+        board.retrieve_nested(client)?;
+
+        // the labels are exported separately from the board
+        let labels = match board.labels.take() {
+            Some(labels) => labels,
+            None => Label::get_all(client, &board.id)?,
+        };
+
+        let mut lists = vec![];
+        for mut list in board.lists.take().unwrap_or_default() {
+            let mut cards = vec![];
+            for mut card in list.cards.take().unwrap_or_default() {
+                debug!("Exporting card {}", card.id);
+                card.checklists = Some(Checklist::get_all(client, &card.id)?);
+
+                cards.push(CardExport {
+                    comments: Comment::get_all(client, &card.id)?,
+                    attachments: Attachment::get_all(client, &card.id)?,
+                    card,
+                });
+            }
+            lists.push(ListExport { list, cards });
+        }
+
+        Ok(BoardExport {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            board,
+            labels,
+            lists,
+        })
+    }
+
+    /// Recreates the exported board under the given name and returns it. All objects
+    /// are given new ids. Comments are posted by the current user, and attachments are
+    /// added as links to the original attachment urls.
+    pub fn import(&self, client: &TrelloClient, name: &str) -> Result<Board> {
+        if self.version > EXPORT_VERSION {
+            return Err(TrelloError::Export(format!(
+                "Unsupported export version {}. This version of tro supports up to version {}",
+                self.version, EXPORT_VERSION
+            )));
+        }
+
+        let board = Board::create_with_defaults(client, name, false, false)?;
+
+        // maps the ids of the exported labels to the ids of their new counterparts
+        let mut label_ids = HashMap::new();
+        for label in &self.labels {
+            let new_label = Label::create(client, &board.id, &label.name, label.color.as_ref())?;
+            label_ids.insert(label.id.clone(), new_label.id);
+        }
+
+        for exported_list in &self.lists {
+            let mut list = List::create(client, &board.id, &exported_list.list.name)?;
+            if exported_list.list.closed {
+                list.closed = true;
+                List::update(client, &list)?;
+            }
+
+            for exported_card in &exported_list.cards {
+                import_card(client, &list.id, exported_card, &label_ids)?;
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+fn import_card(
+    client: &TrelloClient,
+    list_id: &str,
+    exported: &CardExport,
+    label_ids: &HashMap<String, String>,
+) -> Result<Card> {
+    let source = &exported.card;
+    let card = Card::create(client, list_id, source)?;
+
+    for label in source.labels.iter().flatten() {
+        match label_ids.get(&label.id) {
+            Some(label_id) => Label::apply(client, &card.id, label_id)?,
+            None => warn!("Label {} of card {} was not exported", label.id, source.id),
+        }
+    }
+    if source.due.is_some() {
+        Card::set_due(client, &card.id, source.due)?;
+    }
+    if source.due_complete {
+        Card::set_due_complete(client, &card.id, true)?;
+    }
+    if let Some(checklists) = &source.checklists
+        && !checklists.is_empty()
+    {
+        Checklist::sync(client, &card.id, &[], checklists)?;
+    }
+    // comments are returned newest first, so they are posted in reverse
+    for comment in exported.comments.iter().rev() {
+        Comment::create(client, &card.id, &comment.data.text)?;
+    }
+    for attachment in &exported.attachments {
+        Attachment::link(client, &card.id, &attachment.url, &attachment.name)?;
+    }
+
+    Ok(card)
+}
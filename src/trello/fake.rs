@@ -0,0 +1,1152 @@
+use crate::client::{ClientConfig, RetryPolicy, TrelloClient};
+use crate::transport::Transport;
+use crate::trello_error::TrelloError;
+
+use chrono::{SecondsFormat, Utc};
+use reqwest::Method;
+use reqwest::blocking::{Request, Response};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Result<T> = std::result::Result<T, Failure>;
+
+/// Lists which Trello adds to new boards unless `defaultLists=false` is specified
+const DEFAULT_LISTS: &[&str] = &["To Do", "Doing", "Done"];
+/// Colors of the unnamed labels which Trello adds to new boards
+const DEFAULT_LABEL_COLORS: &[&str] = &["green", "yellow", "orange", "red", "purple", "blue"];
+/// Gap which Trello leaves between the positions of consecutive lists and cards
+const POS_GAP: f64 = 16384.0;
+/// Timestamp (2020-07-14) used as the prefix of generated ids, as Trello ids
+/// start with the creation time of the object
+const BASE_TIMESTAMP: u64 = 0x5f0d_6c80;
+
+const ME_ID: &str = "5f0d6c80000000000000000f";
+
+/// In-memory Trello backend which can be used as the Transport of a TrelloClient.
+/// Boards, lists, cards, labels and attachments are kept in memory and change state
+/// in the same way they would on Trello, so code built on this crate can be tested
+/// without a network or hand written responses.
+///
+/// Clones share the same state, so a clone can be handed to a client while the
+/// original is kept around to create further clients.
+/// ```
+/// let fake = trello::FakeTrello::new();
+/// let client = fake.client();
+///
+/// let board = trello::Board::create(&client, "Groceries")?;
+/// let lists = trello::List::get_all(&client, &board.id, false)?;
+/// assert_eq!(lists[0].name, "To Do");
+/// # Ok::<(), trello::TrelloError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeTrello {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeTrello {
+    pub fn new() -> FakeTrello {
+        FakeTrello::default()
+    }
+
+    /// Creates a TrelloClient which is backed by this fake. Requests are not
+    /// retried as the fake never fails transiently.
+    pub fn client(&self) -> TrelloClient {
+        let mut config = ClientConfig::new(&ClientConfig::default_host(), "fake-token", "fake-key");
+        config.retry = RetryPolicy::none();
+
+        TrelloClient::with_transport(config, self.clone())
+    }
+}
+
+impl Transport for FakeTrello {
+    fn execute(&self, request: Request) -> std::result::Result<Response, TrelloError> {
+        let request = FakeRequest::parse(request)?;
+        debug!("Fake Trello received {} {:?}", request.method, request.path);
+
+        let mut state = self.state.lock().expect("Fake Trello state was poisoned");
+        let (status, content_type, body) = match state.handle(&request) {
+            Ok(Reply::Json(value)) => (200, "application/json".into(), value.to_string().into()),
+            Ok(Reply::File(mime_type, content)) => (200, mime_type, content),
+            Err(failure) => (failure.status, "text/plain".into(), failure.message.into()),
+        };
+
+        let response = http::Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, content_type)
+            .body(body)
+            .expect("Failed to build fake response");
+
+        Ok(Response::from(response))
+    }
+}
+
+enum Reply {
+    Json(Value),
+    File(String, Vec<u8>),
+}
+
+struct Failure {
+    status: u16,
+    message: String,
+}
+
+fn not_found() -> Failure {
+    Failure {
+        status: 404,
+        message: String::from("The requested resource was not found."),
+    }
+}
+
+fn invalid(field: &str) -> Failure {
+    Failure {
+        status: 400,
+        message: format!("invalid value for {}", field),
+    }
+}
+
+#[derive(Clone)]
+struct Upload {
+    filename: String,
+    mime_type: String,
+    content: Vec<u8>,
+}
+
+struct FakeRequest {
+    method: Method,
+    path: Vec<String>,
+    params: HashMap<String, String>,
+    upload: Option<Upload>,
+}
+
+impl FakeRequest {
+    /// Collects the query string and body parameters of a request. Trello accepts
+    /// parameters in either, so both are merged together.
+    fn parse(mut request: Request) -> std::result::Result<FakeRequest, TrelloError> {
+        let mut params: HashMap<String, String> =
+            request.url().query_pairs().into_owned().collect();
+        let path = request
+            .url()
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).map(String::from).collect())
+            .unwrap_or_default();
+
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut upload = None;
+        if let Some(body) = request.body_mut() {
+            let body = body.buffer()?;
+
+            if content_type.starts_with("application/x-www-form-urlencoded") {
+                params.extend(url::form_urlencoded::parse(body).into_owned());
+            } else if content_type.starts_with("application/json") {
+                if let Ok(Value::Object(map)) = serde_json::from_slice::<Value>(body) {
+                    for (key, value) in map {
+                        let value = match value {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        params.insert(key, value);
+                    }
+                }
+            } else if let Some(boundary) = content_type.split("boundary=").nth(1) {
+                upload = parse_multipart(body, boundary, &mut params);
+            }
+        }
+
+        Ok(FakeRequest {
+            method: request.method().clone(),
+            path,
+            params,
+            upload,
+        })
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|s| s.as_str())
+    }
+
+    fn flag(&self, name: &str) -> Result<Option<bool>> {
+        match self.param(name) {
+            None => Ok(None),
+            Some("true") => Ok(Some(true)),
+            Some("false") => Ok(Some(false)),
+            Some(_) => Err(invalid(name)),
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Extracts the fields of a multipart body into `params`, returning the file
+/// contained in the body (if any)
+fn parse_multipart(
+    mut body: &[u8],
+    boundary: &str,
+    params: &mut HashMap<String, String>,
+) -> Option<Upload> {
+    let delimiter = format!("--{}", boundary);
+    let mut upload = None;
+
+    while let Some(start) = find(body, delimiter.as_bytes()) {
+        body = &body[start + delimiter.len()..];
+        let end = find(body, delimiter.as_bytes()).unwrap_or(body.len());
+        let part = &body[..end];
+
+        let split = match find(part, b"\r\n\r\n") {
+            Some(split) => split,
+            None => continue,
+        };
+        let headers = String::from_utf8_lossy(&part[..split]);
+        let content = &part[split + 4..];
+        let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+
+        let attribute = |name: &str| {
+            let start = headers.find(&format!("{}=\"", name))? + name.len() + 2;
+            let end = headers[start..].find('"')?;
+            Some(String::from(&headers[start..start + end]))
+        };
+        let mime_type = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("Content-Type: "))
+            .unwrap_or("application/octet-stream");
+
+        match (attribute(" name"), attribute("filename")) {
+            (_, Some(filename)) => {
+                upload = Some(Upload {
+                    filename,
+                    mime_type: String::from(mime_type),
+                    content: content.to_vec(),
+                })
+            }
+            (Some(name), None) => {
+                params.insert(name, String::from_utf8_lossy(content).into_owned());
+            }
+            (None, None) => {}
+        }
+    }
+    upload
+}
+
+#[derive(Debug, Default)]
+struct State {
+    counter: u64,
+    boards: Vec<FakeBoard>,
+    lists: Vec<FakeList>,
+    cards: Vec<FakeCard>,
+    labels: Vec<FakeLabel>,
+    attachments: Vec<FakeAttachment>,
+}
+
+#[derive(Debug)]
+struct FakeBoard {
+    id: String,
+    short_link: String,
+    name: String,
+    desc: String,
+    closed: bool,
+    card_count: u64,
+}
+
+#[derive(Debug)]
+struct FakeList {
+    id: String,
+    id_board: String,
+    name: String,
+    closed: bool,
+    pos: f64,
+}
+
+#[derive(Debug)]
+struct FakeCard {
+    id: String,
+    short_link: String,
+    id_short: u64,
+    id_board: String,
+    id_list: String,
+    name: String,
+    desc: String,
+    closed: bool,
+    pos: f64,
+    due: Option<String>,
+    due_complete: bool,
+    id_labels: Vec<String>,
+    id_members: Vec<String>,
+}
+
+#[derive(Debug)]
+struct FakeLabel {
+    id: String,
+    id_board: String,
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Debug)]
+struct FakeAttachment {
+    id: String,
+    id_card: String,
+    name: String,
+    url: String,
+    date: String,
+    upload: Option<Upload>,
+}
+
+impl std::fmt::Debug for Upload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Upload({}, {} bytes)", self.filename, self.content.len())
+    }
+}
+
+/// Converts a name into the form used in Trello urls, e.g. "My Board" becomes "my-board"
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Only keeps the requested comma separated `fields` of an object (along with its id)
+fn select_fields(value: Value, fields: Option<&str>) -> Value {
+    match (value, fields) {
+        (Value::Object(map), Some(fields)) if fields != "all" => {
+            let fields = fields.split(',').collect::<Vec<&str>>();
+            Value::Object(
+                map.into_iter()
+                    .filter(|(k, _)| k == "id" || fields.contains(&k.as_str()))
+                    .collect(),
+            )
+        }
+        (value, _) => value,
+    }
+}
+
+fn matches_filter(closed: bool, filter: Option<&str>, default: &str) -> Result<bool> {
+    match filter.unwrap_or(default) {
+        "all" => Ok(true),
+        "open" => Ok(!closed),
+        "closed" => Ok(closed),
+        "none" => Ok(false),
+        _ => Err(invalid("filter")),
+    }
+}
+
+impl State {
+    fn handle(&mut self, request: &FakeRequest) -> Result<Reply> {
+        let path = request
+            .path
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<&str>>();
+        let fields = request.param("fields");
+
+        let value = match (&request.method, path.as_slice()) {
+            (&Method::GET, ["1", "members", "me"]) => select_fields(me(), fields),
+            (&Method::GET, ["1", "members", "me", "boards"]) => {
+                let mut boards = vec![];
+                for board in &self.boards {
+                    if matches_filter(board.closed, request.param("filter"), "all")? {
+                        boards.push(select_fields(self.board_json(board), fields));
+                    }
+                }
+                Value::Array(boards)
+            }
+
+            (&Method::POST, ["1", "boards"]) => self.create_board(request)?,
+            (&Method::GET, ["1", "boards", id]) => {
+                let board = self.board(id)?;
+                let mut value = select_fields(self.board_json(board), fields);
+                self.add_nested(&mut value, &board.id, request)?;
+                value
+            }
+            (&Method::PUT, ["1", "boards", id]) => self.update_board(id, request)?,
+            (&Method::GET, ["1", "boards", id, "lists"]) => {
+                let board_id = self.board(id)?.id.clone();
+                let mut lists = vec![];
+                for list in self.lists_of(&board_id) {
+                    if matches_filter(list.closed, request.param("filter"), "open")? {
+                        let mut value = select_fields(self.list_json(list), fields);
+                        if let Some(filter) = request.param("cards") {
+                            value["cards"] = self.cards_json(&list.id, Some(filter))?;
+                        }
+                        lists.push(value);
+                    }
+                }
+                Value::Array(lists)
+            }
+            (&Method::GET, ["1", "boards", id, "labels"]) => {
+                let board_id = self.board(id)?.id.clone();
+                Value::Array(
+                    self.labels
+                        .iter()
+                        .filter(|l| l.id_board == board_id)
+                        .map(|l| select_fields(label_json(l), fields))
+                        .collect(),
+                )
+            }
+            (&Method::GET, ["1", "boards", id, "members"]) => {
+                self.board(id)?;
+                json!([select_fields(me(), fields)])
+            }
+            (&Method::GET, ["1", "boards", id, "customFields"]) => {
+                self.board(id)?;
+                json!([])
+            }
+
+            (&Method::POST, ["1", "lists"]) => self.create_list(request)?,
+            (&Method::GET, ["1", "lists", id]) => {
+                let list = self.list(id)?;
+                select_fields(self.list_json(list), fields)
+            }
+            (&Method::PUT, ["1", "lists", id]) => self.update_list(id, request)?,
+            (&Method::GET, ["1", "lists", id, "cards"]) => {
+                let list_id = self.list(id)?.id.clone();
+                match self.cards_json(&list_id, request.param("filter"))? {
+                    Value::Array(cards) => cards
+                        .into_iter()
+                        .map(|c| select_fields(c, fields))
+                        .collect(),
+                    _ => unreachable!(),
+                }
+            }
+
+            (&Method::POST, ["1", "labels"]) => self.create_label(request)?,
+            (&Method::GET, ["1", "labels", id]) => {
+                select_fields(label_json(self.label(id)?), fields)
+            }
+            (&Method::PUT, ["1", "labels", id]) => self.update_label(id, request)?,
+            (&Method::DELETE, ["1", "labels", id]) => {
+                let label_id = self.label(id)?.id.clone();
+                self.labels.retain(|l| l.id != label_id);
+                for card in &mut self.cards {
+                    card.id_labels.retain(|l| l != &label_id);
+                }
+                json!({})
+            }
+
+            (&Method::POST, ["1", "cards"]) => self.create_card(request)?,
+            (&Method::GET, ["1", "cards", id]) => {
+                let card = self.card(id)?;
+                select_fields(self.card_json(card), fields)
+            }
+            (&Method::PUT, ["1", "cards", id]) => self.update_card(id, request)?,
+            (&Method::DELETE, ["1", "cards", id]) => {
+                let card_id = self.card(id)?.id.clone();
+                self.cards.retain(|c| c.id != card_id);
+                self.attachments.retain(|a| a.id_card != card_id);
+                json!({"limits": {}})
+            }
+            (&Method::POST, ["1", "cards", id, "idLabels"]) => {
+                let label_id = request.param("value").ok_or_else(|| invalid("value"))?;
+                let card = self.card(id)?;
+                match self.labels.iter().find(|l| l.id == label_id) {
+                    Some(label) if label.id_board == card.id_board => {}
+                    _ => return Err(invalid("value")),
+                }
+                if card.id_labels.iter().any(|l| l == label_id) {
+                    return Err(Failure {
+                        status: 400,
+                        message: String::from("that label is already on the card"),
+                    });
+                }
+                let card = self.card_mut(id)?;
+                card.id_labels.push(String::from(label_id));
+                json!(card.id_labels)
+            }
+            (&Method::DELETE, ["1", "cards", id, "idLabels", label_id]) => {
+                let card = self.card_mut(id)?;
+                if !card.id_labels.iter().any(|l| l == label_id) {
+                    return Err(not_found());
+                }
+                card.id_labels.retain(|l| l != label_id);
+                json!([])
+            }
+            (&Method::POST, ["1", "cards", id, "idMembers"]) => {
+                let member_id = request.param("value").ok_or_else(|| invalid("value"))?;
+                if member_id != ME_ID {
+                    return Err(invalid("value"));
+                }
+                let card = self.card_mut(id)?;
+                if !card.id_members.iter().any(|m| m == member_id) {
+                    card.id_members.push(String::from(member_id));
+                }
+                json!([me()])
+            }
+            (&Method::DELETE, ["1", "cards", id, "idMembers", member_id]) => {
+                let card = self.card_mut(id)?;
+                card.id_members.retain(|m| m != member_id);
+                json!([])
+            }
+            (&Method::GET, ["1", "cards", id, "attachments"]) => {
+                let card_id = self.card(id)?.id.clone();
+                Value::Array(
+                    self.attachments
+                        .iter()
+                        .filter(|a| a.id_card == card_id)
+                        .map(|a| select_fields(attachment_json(a), fields))
+                        .collect(),
+                )
+            }
+            (&Method::POST, ["1", "cards", id, "attachments"]) => {
+                self.create_attachment(id, request)?
+            }
+            (&Method::GET, ["1", "cards", id, "attachments", attachment_id]) => {
+                let attachment = self.attachment(id, attachment_id)?;
+                select_fields(attachment_json(attachment), fields)
+            }
+            (
+                &Method::GET,
+                [
+                    "1",
+                    "cards",
+                    id,
+                    "attachments",
+                    attachment_id,
+                    "download",
+                    _,
+                ],
+            ) => {
+                let attachment = self.attachment(id, attachment_id)?;
+                let upload = attachment.upload.as_ref().ok_or_else(not_found)?;
+                return Ok(Reply::File(
+                    upload.mime_type.clone(),
+                    upload.content.clone(),
+                ));
+            }
+            (&Method::DELETE, ["1", "cards", id, "attachments", attachment_id]) => {
+                let attachment_id = self.attachment(id, attachment_id)?.id.clone();
+                self.attachments.retain(|a| a.id != attachment_id);
+                json!({})
+            }
+            // Checklists, comments and custom fields are not modelled
+            (
+                &Method::GET,
+                [
+                    "1",
+                    "cards",
+                    id,
+                    "checklists" | "customFieldItems" | "actions",
+                ],
+            ) => {
+                self.card(id)?;
+                json!([])
+            }
+
+            (&Method::GET, ["1", "search"]) => self.search(request)?,
+
+            (method, _) => {
+                return Err(Failure {
+                    status: 404,
+                    message: format!("Cannot {} /{}", method, path.join("/")),
+                });
+            }
+        };
+
+        Ok(Reply::Json(value))
+    }
+
+    /// Generates a new id in the same format as Trello ids: a creation timestamp
+    /// followed by a counter, 24 hex characters in total
+    fn next_id(&mut self) -> String {
+        self.counter += 1;
+        format!("{:08x}{:016x}", BASE_TIMESTAMP + self.counter, self.counter)
+    }
+
+    /// Generates an 8 character short link such as "aBcD1234"
+    fn next_short_link(&mut self) -> String {
+        const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+        self.counter += 1;
+        let mut value = self.counter.wrapping_mul(2_654_435_761) % 62u64.pow(8);
+        let mut short_link = String::new();
+        for _ in 0..8 {
+            short_link.push(ALPHABET[(value % 62) as usize] as char);
+            value /= 62;
+        }
+        short_link
+    }
+
+    /// Boards and cards can be referenced by their id or their short link
+    fn board(&self, id: &str) -> Result<&FakeBoard> {
+        self.boards
+            .iter()
+            .find(|b| b.id == id || b.short_link == id)
+            .ok_or_else(not_found)
+    }
+
+    fn board_mut(&mut self, id: &str) -> Result<&mut FakeBoard> {
+        self.boards
+            .iter_mut()
+            .find(|b| b.id == id || b.short_link == id)
+            .ok_or_else(not_found)
+    }
+
+    fn list(&self, id: &str) -> Result<&FakeList> {
+        self.lists.iter().find(|l| l.id == id).ok_or_else(not_found)
+    }
+
+    fn card(&self, id: &str) -> Result<&FakeCard> {
+        self.cards
+            .iter()
+            .find(|c| c.id == id || c.short_link == id)
+            .ok_or_else(not_found)
+    }
+
+    fn card_mut(&mut self, id: &str) -> Result<&mut FakeCard> {
+        self.cards
+            .iter_mut()
+            .find(|c| c.id == id || c.short_link == id)
+            .ok_or_else(not_found)
+    }
+
+    fn label(&self, id: &str) -> Result<&FakeLabel> {
+        self.labels
+            .iter()
+            .find(|l| l.id == id)
+            .ok_or_else(not_found)
+    }
+
+    fn attachment(&self, card_id: &str, id: &str) -> Result<&FakeAttachment> {
+        let card_id = &self.card(card_id)?.id;
+        self.attachments
+            .iter()
+            .find(|a| a.id == id && &a.id_card == card_id)
+            .ok_or_else(not_found)
+    }
+
+    /// Lists of a board ordered by position
+    fn lists_of(&self, board_id: &str) -> Vec<&FakeList> {
+        let mut lists = self
+            .lists
+            .iter()
+            .filter(|l| l.id_board == board_id)
+            .collect::<Vec<&FakeList>>();
+        lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        lists
+    }
+
+    /// Cards of a list ordered by position
+    fn cards_of(&self, list_id: &str) -> Vec<&FakeCard> {
+        let mut cards = self
+            .cards
+            .iter()
+            .filter(|c| c.id_list == list_id)
+            .collect::<Vec<&FakeCard>>();
+        cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+        cards
+    }
+
+    /// Resolves the `pos` parameter ("top", "bottom" or a number) given the
+    /// positions of the existing siblings
+    fn position(pos: Option<&str>, siblings: &[f64]) -> Result<f64> {
+        let max = siblings.iter().cloned().fold(0.0, f64::max);
+        let min = siblings.iter().cloned().fold(f64::MAX, f64::min);
+
+        match pos {
+            None | Some("bottom") => Ok(max + POS_GAP),
+            Some("top") if siblings.is_empty() => Ok(POS_GAP),
+            Some("top") => Ok(min / 2.0),
+            Some(value) => value
+                .parse::<f64>()
+                .ok()
+                .filter(|p| *p >= 0.0)
+                .ok_or_else(|| invalid("pos")),
+        }
+    }
+
+    fn create_board(&mut self, request: &FakeRequest) -> Result<Value> {
+        let name = request.param("name").filter(|n| !n.is_empty());
+        let name = name.ok_or_else(|| invalid("name"))?.to_string();
+
+        let board = FakeBoard {
+            id: self.next_id(),
+            short_link: self.next_short_link(),
+            name,
+            desc: request.param("desc").unwrap_or_default().to_string(),
+            closed: false,
+            card_count: 0,
+        };
+        let board_id = board.id.clone();
+        self.boards.push(board);
+
+        if request.flag("defaultLists")?.unwrap_or(true) {
+            for (index, name) in DEFAULT_LISTS.iter().enumerate() {
+                let list = FakeList {
+                    id: self.next_id(),
+                    id_board: board_id.clone(),
+                    name: String::from(*name),
+                    closed: false,
+                    pos: POS_GAP * (index + 1) as f64,
+                };
+                self.lists.push(list);
+            }
+        }
+        if request.flag("defaultLabels")?.unwrap_or(true) {
+            for color in DEFAULT_LABEL_COLORS {
+                let label = FakeLabel {
+                    id: self.next_id(),
+                    id_board: board_id.clone(),
+                    name: String::new(),
+                    color: Some(String::from(*color)),
+                };
+                self.labels.push(label);
+            }
+        }
+
+        Ok(self.board_json(self.board(&board_id)?))
+    }
+
+    fn update_board(&mut self, id: &str, request: &FakeRequest) -> Result<Value> {
+        let closed = request.flag("closed")?;
+        let board = self.board_mut(id)?;
+
+        if let Some(name) = request.param("name") {
+            if name.is_empty() {
+                return Err(invalid("name"));
+            }
+            board.name = String::from(name);
+        }
+        if let Some(desc) = request.param("desc") {
+            board.desc = String::from(desc);
+        }
+        if let Some(closed) = closed {
+            board.closed = closed;
+        }
+
+        Ok(self.board_json(self.board(id)?))
+    }
+
+    fn create_list(&mut self, request: &FakeRequest) -> Result<Value> {
+        let name = request.param("name").filter(|n| !n.is_empty());
+        let name = name.ok_or_else(|| invalid("name"))?.to_string();
+        let board_id = request.param("idBoard").ok_or_else(|| invalid("idBoard"))?;
+        let board_id = self
+            .board(board_id)
+            .map_err(|_| invalid("idBoard"))?
+            .id
+            .clone();
+
+        let siblings = self
+            .lists_of(&board_id)
+            .iter()
+            .map(|l| l.pos)
+            .collect::<Vec<f64>>();
+        let list = FakeList {
+            id: self.next_id(),
+            id_board: board_id,
+            name,
+            closed: request.flag("closed")?.unwrap_or(false),
+            pos: State::position(request.param("pos"), &siblings)?,
+        };
+        let value = self.list_json(&list);
+        self.lists.push(list);
+
+        Ok(value)
+    }
+
+    fn update_list(&mut self, id: &str, request: &FakeRequest) -> Result<Value> {
+        let closed = request.flag("closed")?;
+        let list = self.list(id)?;
+        let siblings = self
+            .lists_of(&list.id_board)
+            .iter()
+            .filter(|l| l.id != list.id)
+            .map(|l| l.pos)
+            .collect::<Vec<f64>>();
+        let pos = match request.param("pos") {
+            Some(pos) => Some(State::position(Some(pos), &siblings)?),
+            None => None,
+        };
+
+        let list = self
+            .lists
+            .iter_mut()
+            .find(|l| l.id == id)
+            .ok_or_else(not_found)?;
+        if let Some(name) = request.param("name") {
+            if name.is_empty() {
+                return Err(invalid("name"));
+            }
+            list.name = String::from(name);
+        }
+        if let Some(closed) = closed {
+            list.closed = closed;
+        }
+        if let Some(pos) = pos {
+            list.pos = pos;
+        }
+
+        Ok(self.list_json(self.list(id)?))
+    }
+
+    fn create_label(&mut self, request: &FakeRequest) -> Result<Value> {
+        let board_id = request.param("idBoard").ok_or_else(|| invalid("idBoard"))?;
+        let board_id = self
+            .board(board_id)
+            .map_err(|_| invalid("idBoard"))?
+            .id
+            .clone();
+
+        let label = FakeLabel {
+            id: self.next_id(),
+            id_board: board_id,
+            name: request.param("name").unwrap_or_default().to_string(),
+            color: parse_color(request.param("color"))?,
+        };
+        let value = label_json(&label);
+        self.labels.push(label);
+
+        Ok(value)
+    }
+
+    fn update_label(&mut self, id: &str, request: &FakeRequest) -> Result<Value> {
+        let color = match request.param("color") {
+            Some(color) => Some(parse_color(Some(color))?),
+            None => None,
+        };
+        let label = self
+            .labels
+            .iter_mut()
+            .find(|l| l.id == id)
+            .ok_or_else(not_found)?;
+
+        if let Some(name) = request.param("name") {
+            label.name = String::from(name);
+        }
+        if let Some(color) = color {
+            label.color = color;
+        }
+
+        Ok(label_json(label))
+    }
+
+    fn create_card(&mut self, request: &FakeRequest) -> Result<Value> {
+        let list_id = request.param("idList").ok_or_else(|| invalid("idList"))?;
+        let list = self.list(list_id).map_err(|_| invalid("idList"))?;
+        let (list_id, board_id) = (list.id.clone(), list.id_board.clone());
+
+        let siblings = self
+            .cards_of(&list_id)
+            .iter()
+            .map(|c| c.pos)
+            .collect::<Vec<f64>>();
+        let pos = State::position(request.param("pos"), &siblings)?;
+
+        let board = self.board_mut(&board_id)?;
+        board.card_count += 1;
+        let id_short = board.card_count;
+
+        let card = FakeCard {
+            id: self.next_id(),
+            short_link: self.next_short_link(),
+            id_short,
+            id_board: board_id,
+            id_list: list_id,
+            name: request.param("name").unwrap_or_default().to_string(),
+            desc: request.param("desc").unwrap_or_default().to_string(),
+            closed: false,
+            pos,
+            due: None,
+            due_complete: false,
+            id_labels: vec![],
+            id_members: vec![],
+        };
+        let value = self.card_json(&card);
+        self.cards.push(card);
+
+        Ok(value)
+    }
+
+    fn update_card(&mut self, id: &str, request: &FakeRequest) -> Result<Value> {
+        let closed = request.flag("closed")?;
+        let due_complete = request.flag("dueComplete")?;
+        let card = self.card(id)?;
+
+        // moving a card to another list places it at the bottom of that list
+        let (list_id, board_id) = match request.param("idList") {
+            Some(list_id) => {
+                let list = self.list(list_id).map_err(|_| invalid("idList"))?;
+                (list.id.clone(), list.id_board.clone())
+            }
+            None => (card.id_list.clone(), card.id_board.clone()),
+        };
+        let siblings = self
+            .cards_of(&list_id)
+            .iter()
+            .filter(|c| c.id != card.id)
+            .map(|c| c.pos)
+            .collect::<Vec<f64>>();
+        let pos = match (request.param("pos"), list_id == card.id_list) {
+            (None, true) => card.pos,
+            (pos, _) => State::position(pos, &siblings)?,
+        };
+
+        let card = self.card_mut(id)?;
+        if let Some(name) = request.param("name") {
+            if name.is_empty() {
+                return Err(invalid("name"));
+            }
+            card.name = String::from(name);
+        }
+        if let Some(desc) = request.param("desc") {
+            card.desc = String::from(desc);
+        }
+        if let Some(closed) = closed {
+            card.closed = closed;
+        }
+        if let Some(due_complete) = due_complete {
+            card.due_complete = due_complete;
+        }
+        if let Some(due) = request.param("due") {
+            card.due = match due {
+                "" | "null" => None,
+                due => Some(
+                    chrono::DateTime::parse_from_rfc3339(due)
+                        .map_err(|_| invalid("due"))?
+                        .with_timezone(&Utc)
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                ),
+            };
+        }
+        card.id_list = list_id;
+        card.id_board = board_id;
+        card.pos = pos;
+
+        Ok(self.card_json(self.card(id)?))
+    }
+
+    fn create_attachment(&mut self, card_id: &str, request: &FakeRequest) -> Result<Value> {
+        let card_id = self.card(card_id)?.id.clone();
+        let id = self.next_id();
+
+        let (name, url) = match (&request.upload, request.param("url")) {
+            (Some(upload), _) => (
+                upload.filename.clone(),
+                format!(
+                    "https://trello.com/1/cards/{}/attachments/{}/download/{}",
+                    card_id, id, upload.filename
+                ),
+            ),
+            (None, Some(url)) => (String::from(url), String::from(url)),
+            (None, None) => return Err(invalid("url")),
+        };
+
+        let attachment = FakeAttachment {
+            id,
+            id_card: card_id,
+            name: request.param("name").map(String::from).unwrap_or(name),
+            url,
+            date: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            upload: request.upload.clone(),
+        };
+        let value = attachment_json(&attachment);
+        self.attachments.push(attachment);
+
+        Ok(value)
+    }
+
+    /// Matches the words of the query against the names of open boards and the
+    /// names and descriptions of open cards, ignoring case
+    fn search(&self, request: &FakeRequest) -> Result<Value> {
+        let query = request.param("query").ok_or_else(|| invalid("query"))?;
+        let words = query
+            .split_whitespace()
+            .map(|w| w.to_lowercase())
+            .collect::<Vec<String>>();
+        let matches = |text: &str| {
+            let text = text.to_lowercase();
+            words.iter().all(|w| text.contains(w.as_str()))
+        };
+        let limit = |name: &str| -> Result<usize> {
+            match request.param(name) {
+                Some(value) => value.parse().map_err(|_| invalid(name)),
+                None => Ok(10),
+            }
+        };
+
+        let boards = self
+            .boards
+            .iter()
+            .filter(|b| !b.closed && matches(&b.name))
+            .take(limit("boards_limit")?)
+            .map(|b| select_fields(self.board_json(b), request.param("board_fields")))
+            .collect::<Vec<Value>>();
+        let cards = self
+            .cards
+            .iter()
+            .filter(|c| !c.closed && matches(&format!("{} {}", c.name, c.desc)))
+            .take(limit("cards_limit")?)
+            .map(|c| select_fields(self.card_json(c), request.param("card_fields")))
+            .collect::<Vec<Value>>();
+
+        Ok(json!({
+            "options": {"terms": words.iter().map(|w| json!({"text": w})).collect::<Vec<Value>>()},
+            "boards": boards,
+            "cards": cards,
+        }))
+    }
+
+    fn board_json(&self, board: &FakeBoard) -> Value {
+        json!({
+            "id": board.id,
+            "name": board.name,
+            "desc": board.desc,
+            "closed": board.closed,
+            "shortLink": board.short_link,
+            "url": format!("https://trello.com/b/{}/{}", board.short_link, slug(&board.name)),
+            "shortUrl": format!("https://trello.com/b/{}", board.short_link),
+        })
+    }
+
+    fn list_json(&self, list: &FakeList) -> Value {
+        json!({
+            "id": list.id,
+            "name": list.name,
+            "closed": list.closed,
+            "idBoard": list.id_board,
+            "pos": list.pos,
+        })
+    }
+
+    fn card_json(&self, card: &FakeCard) -> Value {
+        let labels = card
+            .id_labels
+            .iter()
+            .filter_map(|id| self.labels.iter().find(|l| &l.id == id))
+            .map(label_json)
+            .collect::<Vec<Value>>();
+
+        json!({
+            "id": card.id,
+            "idShort": card.id_short,
+            "shortLink": card.short_link,
+            "name": card.name,
+            "desc": card.desc,
+            "closed": card.closed,
+            "pos": card.pos,
+            "idBoard": card.id_board,
+            "idList": card.id_list,
+            "idLabels": card.id_labels,
+            "labels": labels,
+            "idMembers": card.id_members,
+            "due": card.due,
+            "dueComplete": card.due_complete,
+            "url": format!(
+                "https://trello.com/c/{}/{}-{}",
+                card.short_link,
+                card.id_short,
+                slug(&card.name)
+            ),
+            "shortUrl": format!("https://trello.com/c/{}", card.short_link),
+        })
+    }
+
+    /// Adds the lists, cards, labels and members of a board which are requested
+    /// with the nested resource parameters of Trello to the board
+    fn add_nested(&self, board: &mut Value, board_id: &str, request: &FakeRequest) -> Result<()> {
+        if let Some(filter) = request.param("lists") {
+            let mut lists = vec![];
+            for list in self.lists_of(board_id) {
+                if matches_filter(list.closed, Some(filter), "open")? {
+                    lists.push(select_fields(
+                        self.list_json(list),
+                        request.param("list_fields"),
+                    ));
+                }
+            }
+            board["lists"] = Value::Array(lists);
+        }
+        if let Some(filter) = request.param("cards") {
+            let mut cards = vec![];
+            for list in self.lists_of(board_id) {
+                for card in self.cards_of(&list.id) {
+                    if matches_filter(card.closed, Some(filter), "open")? {
+                        cards.push(select_fields(
+                            self.card_json(card),
+                            request.param("card_fields"),
+                        ));
+                    }
+                }
+            }
+            board["cards"] = Value::Array(cards);
+        }
+        if let Some(filter) = request.param("labels") {
+            board["labels"] = match filter {
+                "all" => self
+                    .labels
+                    .iter()
+                    .filter(|l| l.id_board == board_id)
+                    .map(|l| select_fields(label_json(l), request.param("label_fields")))
+                    .collect(),
+                "none" => json!([]),
+                _ => return Err(invalid("labels")),
+            };
+        }
+        if request.param("members").is_some() {
+            board["members"] = json!([select_fields(me(), request.param("member_fields"))]);
+        }
+        Ok(())
+    }
+
+    /// Cards of a list matching the given filter (open by default)
+    fn cards_json(&self, list_id: &str, filter: Option<&str>) -> Result<Value> {
+        let mut cards = vec![];
+        for card in self.cards_of(list_id) {
+            if matches_filter(card.closed, filter, "open")? {
+                cards.push(self.card_json(card));
+            }
+        }
+        Ok(Value::Array(cards))
+    }
+}
+
+fn parse_color(color: Option<&str>) -> Result<Option<String>> {
+    match color {
+        None | Some("") | Some("null") => Ok(None),
+        Some(color) if color.chars().all(|c| c.is_ascii_lowercase() || c == '_') => {
+            Ok(Some(String::from(color)))
+        }
+        Some(_) => Err(invalid("color")),
+    }
+}
+
+fn label_json(label: &FakeLabel) -> Value {
+    json!({
+        "id": label.id,
+        "idBoard": label.id_board,
+        "name": label.name,
+        "color": label.color,
+    })
+}
+
+fn attachment_json(attachment: &FakeAttachment) -> Value {
+    let upload = attachment.upload.as_ref();
+
+    json!({
+        "id": attachment.id,
+        "name": attachment.name,
+        "url": attachment.url,
+        "date": attachment.date,
+        "bytes": upload.map(|u| u.content.len()),
+        "mimeType": upload.map(|u| u.mime_type.as_str()).unwrap_or_default(),
+        "isUpload": upload.is_some(),
+    })
+}
+
+fn me() -> Value {
+    json!({
+        "id": ME_ID,
+        "fullName": "Fake User",
+        "username": "fakeuser",
+        "initials": "FU",
+    })
+}
@@ -3,17 +3,247 @@ use crate::trello_error::TrelloError;
 use crate::trello_object::{Renderable, TrelloObject};
 
 use colored::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
 // https://developers.trello.com/reference/#label-object
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Label {
     pub id: String,
     pub name: String,
-    pub color: String,
+    /// Labels without a color have a null color
+    #[serde(default)]
+    pub color: Option<LabelColor>,
+}
+
+/// Colors which Trello labels can have. Each color comes in a subtle (`_light`),
+/// normal and bold (`_dark`) variant.
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum LabelColor {
+    GreenLight,
+    Green,
+    GreenDark,
+    YellowLight,
+    Yellow,
+    YellowDark,
+    OrangeLight,
+    Orange,
+    OrangeDark,
+    RedLight,
+    Red,
+    RedDark,
+    PurpleLight,
+    Purple,
+    PurpleDark,
+    BlueLight,
+    Blue,
+    BlueDark,
+    SkyLight,
+    Sky,
+    SkyDark,
+    LimeLight,
+    Lime,
+    LimeDark,
+    PinkLight,
+    Pink,
+    PinkDark,
+    BlackLight,
+    Black,
+    BlackDark,
+    /// A color returned by Trello which is not part of the palette known to tro
+    Other(String),
+}
+
+impl LabelColor {
+    /// Every color of the palette, ordered as in the Trello label color picker
+    pub const PALETTE: &'static [LabelColor] = &[
+        LabelColor::GreenLight,
+        LabelColor::YellowLight,
+        LabelColor::OrangeLight,
+        LabelColor::RedLight,
+        LabelColor::PurpleLight,
+        LabelColor::Green,
+        LabelColor::Yellow,
+        LabelColor::Orange,
+        LabelColor::Red,
+        LabelColor::Purple,
+        LabelColor::GreenDark,
+        LabelColor::YellowDark,
+        LabelColor::OrangeDark,
+        LabelColor::RedDark,
+        LabelColor::PurpleDark,
+        LabelColor::BlueLight,
+        LabelColor::SkyLight,
+        LabelColor::LimeLight,
+        LabelColor::PinkLight,
+        LabelColor::BlackLight,
+        LabelColor::Blue,
+        LabelColor::Sky,
+        LabelColor::Lime,
+        LabelColor::Pink,
+        LabelColor::Black,
+        LabelColor::BlueDark,
+        LabelColor::SkyDark,
+        LabelColor::LimeDark,
+        LabelColor::PinkDark,
+        LabelColor::BlackDark,
+    ];
+
+    /// Name of the color as used by the Trello API
+    pub fn as_str(&self) -> &str {
+        match self {
+            LabelColor::GreenLight => "green_light",
+            LabelColor::Green => "green",
+            LabelColor::GreenDark => "green_dark",
+            LabelColor::YellowLight => "yellow_light",
+            LabelColor::Yellow => "yellow",
+            LabelColor::YellowDark => "yellow_dark",
+            LabelColor::OrangeLight => "orange_light",
+            LabelColor::Orange => "orange",
+            LabelColor::OrangeDark => "orange_dark",
+            LabelColor::RedLight => "red_light",
+            LabelColor::Red => "red",
+            LabelColor::RedDark => "red_dark",
+            LabelColor::PurpleLight => "purple_light",
+            LabelColor::Purple => "purple",
+            LabelColor::PurpleDark => "purple_dark",
+            LabelColor::BlueLight => "blue_light",
+            LabelColor::Blue => "blue",
+            LabelColor::BlueDark => "blue_dark",
+            LabelColor::SkyLight => "sky_light",
+            LabelColor::Sky => "sky",
+            LabelColor::SkyDark => "sky_dark",
+            LabelColor::LimeLight => "lime_light",
+            LabelColor::Lime => "lime",
+            LabelColor::LimeDark => "lime_dark",
+            LabelColor::PinkLight => "pink_light",
+            LabelColor::Pink => "pink",
+            LabelColor::PinkDark => "pink_dark",
+            LabelColor::BlackLight => "black_light",
+            LabelColor::Black => "black",
+            LabelColor::BlackDark => "black_dark",
+            LabelColor::Other(color) => color,
+        }
+    }
+
+    /// Background color used when rendering a label with this color
+    fn background(&self) -> Color {
+        let (r, g, b) = match self {
+            // values retrieved by inspecting elements in a browser on trello.com
+            // date obtained: 2020-07-14
+            LabelColor::Green => (0x61, 0xbd, 0x4f),
+            LabelColor::Yellow => (0xf2, 0xd6, 0x00),
+            LabelColor::Orange => (0xff, 0x9f, 0x1a),
+            LabelColor::Red => (0xeb, 0x5a, 0x46),
+            LabelColor::Purple => (0xc3, 0x77, 0xe0),
+            LabelColor::Blue => (0x00, 0x79, 0xbf),
+            LabelColor::Sky => (0x00, 0xc2, 0xe0),
+            LabelColor::Lime => (0x51, 0xe8, 0x98),
+            LabelColor::Pink => (0xff, 0x78, 0xcb),
+            LabelColor::Black => (0x34, 0x45, 0x63),
+            // subtle and bold variants, taken from the same source
+            // date obtained: 2026-10-18
+            LabelColor::GreenLight => (0xba, 0xf3, 0xdb),
+            LabelColor::GreenDark => (0x1f, 0x84, 0x5a),
+            LabelColor::YellowLight => (0xf8, 0xe6, 0xa0),
+            LabelColor::YellowDark => (0x94, 0x6f, 0x00),
+            LabelColor::OrangeLight => (0xfe, 0xde, 0xc8),
+            LabelColor::OrangeDark => (0xc2, 0x51, 0x00),
+            LabelColor::RedLight => (0xff, 0xd5, 0xd2),
+            LabelColor::RedDark => (0xc9, 0x37, 0x2c),
+            LabelColor::PurpleLight => (0xdf, 0xd8, 0xfd),
+            LabelColor::PurpleDark => (0x6e, 0x5d, 0xc6),
+            LabelColor::BlueLight => (0xcc, 0xe0, 0xff),
+            LabelColor::BlueDark => (0x0c, 0x66, 0xe4),
+            LabelColor::SkyLight => (0xc6, 0xed, 0xfb),
+            LabelColor::SkyDark => (0x22, 0x7d, 0x9b),
+            LabelColor::LimeLight => (0xd3, 0xf1, 0xa7),
+            LabelColor::LimeDark => (0x5b, 0x7f, 0x24),
+            LabelColor::PinkLight => (0xfd, 0xd0, 0xec),
+            LabelColor::PinkDark => (0xae, 0x47, 0x87),
+            LabelColor::BlackLight => (0xdc, 0xdf, 0xe4),
+            LabelColor::BlackDark => (0x62, 0x6f, 0x86),
+            LabelColor::Other(_) => return NO_COLOR,
+        };
+        Color::TrueColor { r, g, b }
+    }
+
+    /// Text color which is readable on top of the background color
+    fn foreground(&self) -> Color {
+        match self {
+            LabelColor::GreenLight
+            | LabelColor::YellowLight
+            | LabelColor::OrangeLight
+            | LabelColor::RedLight
+            | LabelColor::PurpleLight
+            | LabelColor::BlueLight
+            | LabelColor::SkyLight
+            | LabelColor::LimeLight
+            | LabelColor::PinkLight
+            | LabelColor::BlackLight => Color::Black,
+            _ => Color::White,
+        }
+    }
+}
+
+/// Background of labels without a color (or with a color unknown to tro)
+const NO_COLOR: Color = Color::TrueColor {
+    r: 0x8a,
+    g: 0x93,
+    b: 0xa3,
+};
+
+impl From<String> for LabelColor {
+    fn from(value: String) -> LabelColor {
+        LabelColor::PALETTE
+            .iter()
+            .find(|c| c.as_str() == value)
+            .cloned()
+            .unwrap_or(LabelColor::Other(value))
+    }
+}
+
+impl From<LabelColor> for String {
+    fn from(value: LabelColor) -> String {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for LabelColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for LabelColor {
+    type Err = TrelloError;
+
+    /// Parses a color of the palette, rejecting any other value
+    /// ```
+    /// use trello::LabelColor;
+    ///
+    /// assert_eq!("sky_light".parse::<LabelColor>().unwrap(), LabelColor::SkyLight);
+    /// assert!("turquoise".parse::<LabelColor>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<LabelColor> {
+        match LabelColor::from(value.to_string()) {
+            LabelColor::Other(value) => Err(TrelloError::LabelColor(format!(
+                "Unknown color '{}'. Valid colors are: {}",
+                value,
+                LabelColor::PALETTE
+                    .iter()
+                    .map(|c| c.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ))),
+            color => Ok(color),
+        }
+    }
 }
 
 impl TrelloObject for Label {
@@ -35,20 +265,38 @@ impl Renderable for Label {
         self.simple_render()
     }
 
+    /// Unnamed labels are rendered as a swatch of their color, or as the name
+    /// of their color when output is not colored.
     fn simple_render(&self) -> String {
-        format!(" {} ", self.name)
-            .color(Color::White)
-            .on_color(map_color(&self.color))
+        let text = match (self.name.is_empty(), &self.color) {
+            (false, _) => self.name.as_str(),
+            (true, Some(color)) if !colored::control::SHOULD_COLORIZE.should_colorize() => {
+                color.as_str()
+            }
+            (true, _) => "  ",
+        };
+        let (foreground, background) = match &self.color {
+            Some(color) => (color.foreground(), color.background()),
+            None => (Color::White, NO_COLOR),
+        };
+
+        format!(" {} ", text)
+            .color(foreground)
+            .on_color(background)
             .to_string()
     }
 }
 
 impl Label {
+    /// Creates a label. An empty color creates a label without a color.
     pub fn new(id: &str, name: &str, color: &str) -> Label {
         Label {
             id: String::from(id),
             name: String::from(name),
-            color: String::from(color),
+            color: match color {
+                "" => None,
+                color => Some(LabelColor::from(color.to_string())),
+            },
         }
     }
 
@@ -60,7 +308,45 @@ impl Label {
             &[("fields", &fields)],
         )?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+
+    /// Creates a label on a board. A color of None creates a label without a color.
+    pub fn create(
+        client: &TrelloClient,
+        board_id: &str,
+        name: &str,
+        color: Option<&LabelColor>,
+    ) -> Result<Label> {
+        let url = client.config.get_trello_url("/1/labels/", &[])?;
+
+        let color = color.map(|c| c.as_str()).unwrap_or("null");
+        let params = [("name", name), ("color", color), ("idBoard", board_id)];
+
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
+    }
+
+    /// Updates the name and color of a label. A color of None removes the color of the label.
+    pub fn update(client: &TrelloClient, label: &Label) -> Result<Label> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/labels/{}", &label.id), &[])?;
+
+        let color = label.color.as_ref().map(|c| c.as_str()).unwrap_or("null");
+        let params = [("name", label.name.as_str()), ("color", color)];
+
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
+    }
+
+    /// Deletes a label from its board, removing it from any cards it is applied to
+    pub fn delete(client: &TrelloClient, label_id: &str) -> Result<()> {
+        let url = client
+            .config
+            .get_trello_url(&format!("/1/labels/{}", label_id), &[])?;
+
+        client.send(client.client.delete(url))?;
+
+        Ok(())
     }
 
     pub fn remove(client: &TrelloClient, card_id: &str, label_id: &str) -> Result<()> {
@@ -68,7 +354,7 @@ impl Label {
             .config
             .get_trello_url(&format!("/1/cards/{}/idLabels/{}", card_id, label_id), &[])?;
 
-        client.client.delete(url).send()?.error_for_status()?;
+        client.send(client.client.delete(url))?;
 
         Ok(())
     }
@@ -80,74 +366,8 @@ impl Label {
 
         let params = [("value", label_id)];
 
-        client
-            .client
-            .post(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?;
+        client.send(client.client.post(url).form(&params))?;
 
         Ok(())
     }
 }
-
-fn map_color(color: &str) -> Color {
-    match color {
-        // values retrieved by inspecting elements in a browser on trello.com
-        // date obtained: 2020-07-14
-        "sky" => Color::TrueColor {
-            r: 0x00,
-            g: 0xc2,
-            b: 0xe0,
-        },
-        "lime" => Color::TrueColor {
-            r: 0x51,
-            g: 0xe8,
-            b: 0x98,
-        },
-        "green" => Color::TrueColor {
-            r: 0x61,
-            g: 0xbd,
-            b: 0x4f,
-        },
-        "purple" => Color::TrueColor {
-            r: 0xc3,
-            g: 0x77,
-            b: 0xe0,
-        },
-        "orange" => Color::TrueColor {
-            r: 0xff,
-            g: 0x9f,
-            b: 0x1a,
-        },
-        "yellow" => Color::TrueColor {
-            r: 0xf2,
-            g: 0xd6,
-            b: 0x00,
-        },
-        "red" => Color::TrueColor {
-            r: 0xeb,
-            g: 0x5a,
-            b: 0x46,
-        },
-        "blue" => Color::TrueColor {
-            r: 0x00,
-            g: 0x79,
-            b: 0xbf,
-        },
-        "pink" => Color::TrueColor {
-            r: 0xff,
-            g: 0x78,
-            b: 0xcb,
-        },
-        "black" => Color::TrueColor {
-            r: 0x34,
-            g: 0x45,
-            b: 0x63,
-        },
-        value => {
-            println!("Unknown color: {}", value);
-            Color::from(color)
-        }
-    }
-}
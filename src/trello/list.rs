@@ -6,17 +6,18 @@ use crate::trello_object::{Renderable, TrelloObject};
 
 use colored::*;
 use regex::RegexBuilder;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
 // https://developers.trello.com/reference/#list-object
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct List {
     pub id: String,
     pub name: String,
     pub closed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub cards: Option<Vec<Card>>,
 }
 
@@ -128,13 +129,7 @@ impl List {
 
         let params = [("name", name), ("idBoard", board_id)];
 
-        Ok(client
-            .client
-            .post(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.post(url).form(&params))?.json()?)
     }
 
     pub fn open(client: &TrelloClient, list_id: &str) -> Result<List> {
@@ -144,13 +139,7 @@ impl List {
 
         let params = [("closed", "false")];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     pub fn update(client: &TrelloClient, list: &List) -> Result<List> {
@@ -160,13 +149,7 @@ impl List {
 
         let params = [("name", &list.name), ("closed", &list.closed.to_string())];
 
-        Ok(client
-            .client
-            .put(url)
-            .form(&params)
-            .send()?
-            .error_for_status()?
-            .json()?)
+        Ok(client.send(client.client.put(url).form(&params))?.json()?)
     }
 
     pub fn get_all(client: &TrelloClient, board_id: &str, cards: bool) -> Result<Vec<List>> {
@@ -181,6 +164,6 @@ impl List {
             .config
             .get_trello_url(&format!("/1/boards/{}/lists", board_id), &params)?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 }
@@ -1,22 +1,67 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::client::TrelloClient;
 use crate::trello_error::TrelloError;
+use crate::trello_object::{Renderable, TrelloObject};
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Member {
     pub id: String,
     pub full_name: String,
     pub username: String,
+    #[serde(default)]
+    pub initials: String,
+}
+
+impl TrelloObject for Member {
+    fn get_type() -> String {
+        String::from("Member")
+    }
+
+    fn get_name(&self) -> &str {
+        &self.username
+    }
+
+    fn get_fields() -> &'static [&'static str] {
+        &["id", "fullName", "username", "initials"]
+    }
+}
+
+impl Renderable for Member {
+    fn render(&self, _: bool) -> String {
+        self.simple_render()
+    }
+
+    fn simple_render(&self) -> String {
+        format!("{} (@{})", self.full_name, self.username)
+    }
 }
 
 impl Member {
+    pub fn new(id: &str, full_name: &str, username: &str, initials: &str) -> Member {
+        Member {
+            id: String::from(id),
+            full_name: String::from(full_name),
+            username: String::from(username),
+            initials: String::from(initials),
+        }
+    }
+
     pub fn me(client: &TrelloClient) -> Result<Member> {
         let url = client.config.get_trello_url("/1/members/me/", &[])?;
 
-        Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+        Ok(client.send(client.client.get(url))?.json()?)
+    }
+
+    pub fn get_all_for_board(client: &TrelloClient, board_id: &str) -> Result<Vec<Member>> {
+        let url = client.config.get_trello_url(
+            &format!("/1/boards/{}/members", board_id),
+            &[("fields", &Member::get_fields().join(","))],
+        )?;
+
+        Ok(client.send(client.client.get(url))?.json()?)
     }
 }
@@ -2,28 +2,62 @@
 extern crate log;
 
 mod attachment;
+mod authorize;
 mod board;
+mod cache;
 mod card;
+mod cassette;
+mod checklist;
 mod client;
+mod comment;
+mod custom_field;
+mod export;
 mod formatting;
 mod label;
 mod list;
 mod member;
+mod offline;
+mod profiles;
 mod search;
+mod template;
+mod transport;
 mod trello_error;
 mod trello_object;
 
+#[cfg(any(test, feature = "fake"))]
+mod fake;
+
 #[cfg(test)]
 mod tests;
 
 pub use attachment::Attachment;
+pub use authorize::{AuthorizeOptions, LoopbackListener};
 pub use board::Board;
+pub use cache::{CacheConfig, CachingTransport};
 pub use card::{Card, CardContents};
-pub use client::{ClientConfig, TrelloClient};
+pub use cassette::{
+    Cassette, CassetteConfig, CassetteMode, Interaction, RecordedRequest, RecordedResponse,
+    RecordingTransport, ReplayTransport,
+};
+pub use checklist::{CheckItem, CheckItemState, Checklist};
+pub use client::{ClientConfig, RetryPolicy, TrelloClient};
+pub use comment::{Comment, CommentData};
+pub use custom_field::{
+    CustomField, CustomFieldItem, CustomFieldItemValue, CustomFieldOption, CustomFieldType,
+    CustomFieldValue,
+};
+pub use export::{BoardExport, CardExport, EXPORT_VERSION, ListExport};
 pub use formatting::{header, title};
-pub use label::Label;
+pub use label::{Label, LabelColor};
 pub use list::List;
 pub use member::Member;
+pub use offline::OfflineTrello;
+pub use profiles::{DEFAULT_PROFILE, Profiles};
 pub use search::{SearchOptions, SearchResult, search};
+pub use template::{AppliedTemplate, BoardTemplate, CardTemplate, LabelTemplate, ListTemplate};
+pub use transport::{HttpTransport, Transport};
 pub use trello_error::TrelloError;
 pub use trello_object::{Renderable, TrelloObject};
+
+#[cfg(any(test, feature = "fake"))]
+pub use fake::FakeTrello;
@@ -0,0 +1,592 @@
+use crate::attachment::Attachment;
+use crate::board::Board;
+use crate::card::Card;
+use crate::checklist::{CheckItem, CheckItemState, Checklist};
+use crate::client::{ClientConfig, RetryPolicy, TrelloClient};
+use crate::comment::{Comment, CommentData};
+use crate::custom_field::CustomFieldItem;
+use crate::label::Label;
+use crate::list::List;
+use crate::member::Member;
+use crate::transport::Transport;
+use crate::trello_error::TrelloError;
+
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use reqwest::blocking::{Request, Response};
+use reqwest::header::CONTENT_TYPE;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+/// Board as it appears in the JSON export which Trello offers from the board menu
+/// ("Print, export and share" > "Export as JSON"). Only the parts used by tro are read.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedBoard {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    lists: Vec<ExportedList>,
+    #[serde(default)]
+    cards: Vec<ExportedCard>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    checklists: Vec<ExportedChecklist>,
+    #[serde(default)]
+    actions: Vec<ExportedAction>,
+    #[serde(default)]
+    members: Vec<Member>,
+    /// Served as they are, since they are only ever read back by CustomField
+    #[serde(default)]
+    custom_fields: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedList {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedCard {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    url: String,
+    id_list: String,
+    #[serde(default)]
+    id_labels: Vec<String>,
+    #[serde(default)]
+    id_members: Vec<String>,
+    #[serde(default)]
+    pos: f64,
+    id_short: Option<u64>,
+    due: Option<DateTime<Utc>>,
+    #[serde(default)]
+    due_complete: bool,
+    #[serde(default)]
+    attachments: Vec<Attachment>,
+    custom_field_items: Option<Vec<CustomFieldItem>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedChecklist {
+    id: String,
+    name: String,
+    id_card: String,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    check_items: Vec<ExportedCheckItem>,
+}
+
+#[derive(Deserialize)]
+struct ExportedCheckItem {
+    id: String,
+    name: String,
+    state: CheckItemState,
+    #[serde(default)]
+    pos: f64,
+}
+
+/// Only comments are read from the actions of a board, all other actions are ignored
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedAction {
+    id: String,
+    #[serde(rename = "type")]
+    action_type: String,
+    date: DateTime<Utc>,
+    #[serde(default)]
+    data: ExportedActionData,
+    member_creator: Option<Member>,
+}
+
+#[derive(Deserialize, Default)]
+struct ExportedActionData {
+    text: Option<String>,
+    card: Option<ExportedActionCard>,
+}
+
+#[derive(Deserialize)]
+struct ExportedActionCard {
+    id: String,
+}
+
+struct OfflineList {
+    list: List,
+    pos: f64,
+}
+
+struct OfflineCard {
+    card: Card,
+    id_list: String,
+    pos: f64,
+    attachments: Vec<Attachment>,
+}
+
+/// Contents of an exported board, converted into the types of this crate
+struct OfflineBoard {
+    board: Board,
+    lists: Vec<OfflineList>,
+    cards: Vec<OfflineCard>,
+    labels: Vec<Label>,
+    checklists: HashMap<String, Vec<Checklist>>,
+    comments: HashMap<String, Vec<Comment>>,
+    members: Vec<Member>,
+    custom_fields: Vec<Value>,
+}
+
+/// Read-only backend which answers the queries of a TrelloClient from a board
+/// exported as JSON from Trello, without a network or credentials. The exported
+/// board is the only board available. Requests which would modify objects fail with
+/// a `TrelloError::Offline` error.
+/// ```
+/// let offline = trello::OfflineTrello::from_json(
+///     r#"{"id": "b1", "name": "Audit", "lists": [{"id": "l1", "name": "Done"}]}"#,
+/// )?;
+/// let client = offline.client();
+///
+/// let boards = trello::Board::get_all(&client)?;
+/// assert_eq!(boards[0].name, "Audit");
+/// assert!(trello::Board::create(&client, "New").is_err());
+/// # Ok::<(), trello::TrelloError>(())
+/// ```
+#[derive(Clone)]
+pub struct OfflineTrello {
+    export: Arc<OfflineBoard>,
+}
+
+impl std::fmt::Debug for OfflineTrello {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "OfflineTrello({})", self.export.board.name)
+    }
+}
+
+impl OfflineTrello {
+    pub fn load(path: &Path) -> Result<OfflineTrello> {
+        let contents = fs::read_to_string(path)?;
+        OfflineTrello::from_json(&contents).map_err(|e| match e {
+            TrelloError::Offline(message) => {
+                TrelloError::Offline(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    pub fn from_json(contents: &str) -> Result<OfflineTrello> {
+        let exported: ExportedBoard = serde_json::from_str(contents)
+            .map_err(|e| TrelloError::Offline(format!("Invalid Trello board export: {}", e)))?;
+
+        Ok(OfflineTrello {
+            export: Arc::new(OfflineBoard::from(exported)),
+        })
+    }
+
+    /// Creates a client which reads from the export. No credentials are needed.
+    pub fn client(&self) -> TrelloClient {
+        let mut config = ClientConfig::new(&ClientConfig::default_host(), "", "");
+        config.retry = RetryPolicy::none();
+
+        TrelloClient::with_transport(config, self.clone())
+    }
+}
+
+impl From<ExportedBoard> for OfflineBoard {
+    fn from(exported: ExportedBoard) -> OfflineBoard {
+        let labels = exported.labels;
+
+        let mut lists = exported
+            .lists
+            .into_iter()
+            .map(|l| {
+                let mut list = List::new(&l.id, &l.name, None);
+                list.closed = l.closed;
+                OfflineList { list, pos: l.pos }
+            })
+            .collect::<Vec<OfflineList>>();
+        lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut cards = exported
+            .cards
+            .into_iter()
+            .map(|c| {
+                let card_labels = c
+                    .id_labels
+                    .iter()
+                    .filter_map(|id| labels.iter().find(|l| &l.id == id))
+                    .cloned()
+                    .collect();
+
+                let mut card = Card::new(&c.id, &c.name, &c.desc, Some(card_labels), &c.url, c.due);
+                card.closed = c.closed;
+                card.due_complete = c.due_complete;
+                card.id_members = c.id_members;
+                card.id_board = exported.id.clone();
+                card.id_short = c.id_short;
+                card.custom_field_items = c.custom_field_items;
+
+                OfflineCard {
+                    card,
+                    id_list: c.id_list,
+                    pos: c.pos,
+                    attachments: c.attachments,
+                }
+            })
+            .collect::<Vec<OfflineCard>>();
+        cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut exported_checklists = exported.checklists;
+        exported_checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        let mut checklists: HashMap<String, Vec<Checklist>> = HashMap::new();
+        for mut checklist in exported_checklists {
+            checklist
+                .check_items
+                .sort_by(|a, b| a.pos.total_cmp(&b.pos));
+            let items = checklist
+                .check_items
+                .iter()
+                .map(|i| CheckItem::new(&i.id, &i.name, i.state))
+                .collect();
+            checklists
+                .entry(checklist.id_card)
+                .or_default()
+                .push(Checklist::new(&checklist.id, &checklist.name, items));
+        }
+
+        // actions are exported newest first, which is also the order Trello returns comments in
+        let mut comments: HashMap<String, Vec<Comment>> = HashMap::new();
+        for action in exported.actions {
+            if action.action_type != "commentCard" {
+                continue;
+            }
+            if let (Some(text), Some(card), Some(member)) =
+                (action.data.text, action.data.card, action.member_creator)
+            {
+                comments.entry(card.id).or_default().push(Comment {
+                    id: action.id,
+                    date: action.date,
+                    data: CommentData { text },
+                    member_creator: member,
+                });
+            }
+        }
+
+        let mut board = Board::new(&exported.id, &exported.name, None, &exported.url);
+        board.closed = exported.closed;
+
+        OfflineBoard {
+            board,
+            lists,
+            cards,
+            labels,
+            checklists,
+            comments,
+            members: exported.members,
+            custom_fields: exported.custom_fields,
+        }
+    }
+}
+
+impl Transport for OfflineTrello {
+    fn execute(&self, request: Request) -> Result<Response> {
+        let path = request
+            .url()
+            .path_segments()
+            .map(|s| {
+                s.filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        let params: HashMap<String, String> = request.url().query_pairs().into_owned().collect();
+
+        if request.method() != Method::GET {
+            return Err(TrelloError::Offline(format!(
+                "Cannot {} /{} as the export is read-only",
+                request.method(),
+                path.join("/")
+            )));
+        }
+        debug!("Offline Trello received GET {:?}", path);
+
+        let path = path.iter().map(|s| s.as_str()).collect::<Vec<&str>>();
+        let (status, body) = match self.export.handle(&path, &params)? {
+            Some(value) => (200, value.to_string()),
+            None => (404, String::from("The requested resource was not found.")),
+        };
+
+        let response = http::Response::builder()
+            .status(status)
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .expect("Failed to build offline response");
+
+        Ok(Response::from(response))
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("Unable to serialize offline object")
+}
+
+/// Whether an object which is `closed` matches a Trello `filter` parameter
+fn matches_filter(closed: bool, filter: Option<&String>, default: &str) -> bool {
+    match filter.map(|f| f.as_str()).unwrap_or(default) {
+        "all" => true,
+        "open" => !closed,
+        "closed" => closed,
+        _ => false,
+    }
+}
+
+impl OfflineBoard {
+    /// Returns the response body of a GET request, or None if the requested object
+    /// is not part of the export
+    fn handle(&self, path: &[&str], params: &HashMap<String, String>) -> Result<Option<Value>> {
+        let filter = params.get("filter");
+
+        let value = match path {
+            ["1", "members", "me", "boards"] => {
+                match matches_filter(self.board.closed, filter, "all") {
+                    true => json!([to_json(&self.board)]),
+                    false => json!([]),
+                }
+            }
+            ["1", "boards", id] if self.is_board(id) => self.board_json(params),
+            ["1", "boards", id, "lists"] if self.is_board(id) => {
+                let cards_filter = params.get("cards");
+                let lists = self
+                    .lists
+                    .iter()
+                    .filter(|l| matches_filter(l.list.closed, filter, "open"))
+                    .map(|l| {
+                        let mut list = l.list.clone();
+                        if cards_filter.is_some() {
+                            list.cards = Some(self.cards_of(&list.id, cards_filter));
+                        }
+                        list
+                    })
+                    .collect::<Vec<List>>();
+                to_json(&lists)
+            }
+            ["1", "boards", id, "labels"] if self.is_board(id) => to_json(&self.labels),
+            ["1", "boards", id, "members"] if self.is_board(id) => to_json(&self.members),
+            ["1", "boards", id, "customFields"] if self.is_board(id) => {
+                to_json(&self.custom_fields)
+            }
+
+            ["1", "lists", id] => match self.lists.iter().find(|l| &l.list.id == id) {
+                Some(list) => to_json(&list.list),
+                None => return Ok(None),
+            },
+            ["1", "lists", id, "cards"] if self.lists.iter().any(|l| &l.list.id == id) => {
+                to_json(&self.cards_of(id, filter))
+            }
+
+            ["1", "labels", id] => match self.labels.iter().find(|l| &l.id == id) {
+                Some(label) => to_json(label),
+                None => return Ok(None),
+            },
+
+            ["1", "cards", id, rest @ ..] => {
+                let card = match self.cards.iter().find(|c| &c.card.id == id) {
+                    Some(card) => card,
+                    None => return Ok(None),
+                };
+                match rest {
+                    [] => to_json(&card.card),
+                    ["checklists"] => to_json(self.checklists.get(*id).unwrap_or(&vec![])),
+                    ["actions"] => to_json(&self.comments_of(id, params)?),
+                    ["attachments"] => to_json(&card.attachments),
+                    ["attachments", attachment_id] => {
+                        match card.attachments.iter().find(|a| &a.id == attachment_id) {
+                            Some(attachment) => to_json(attachment),
+                            None => return Ok(None),
+                        }
+                    }
+                    ["customFieldItems"] => {
+                        to_json(card.card.custom_field_items.as_ref().unwrap_or(&vec![]))
+                    }
+                    _ => return Ok(None),
+                }
+            }
+
+            ["1", "search"] => self.search(params)?,
+
+            ["1", "members", "me"] => {
+                return Err(TrelloError::Offline(String::from(
+                    "The current member is not known when reading from an export",
+                )));
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+
+    fn is_board(&self, id: &str) -> bool {
+        self.board.id == id
+    }
+
+    /// The board along with the lists, cards, labels and members requested with
+    /// the nested resource parameters of Trello
+    fn board_json(&self, params: &HashMap<String, String>) -> Value {
+        let mut board = to_json(&self.board);
+        if let Some(filter) = params.get("lists") {
+            let lists = self
+                .lists
+                .iter()
+                .filter(|l| matches_filter(l.list.closed, Some(filter), "open"))
+                .map(|l| to_json(&l.list))
+                .collect();
+            board["lists"] = Value::Array(lists);
+        }
+        if let Some(filter) = params.get("cards") {
+            // cards are returned alongside the lists of the board rather than within them
+            let cards = self
+                .cards
+                .iter()
+                .filter(|c| matches_filter(c.card.closed, Some(filter), "open"))
+                .map(|c| {
+                    let mut card = to_json(&c.card);
+                    card["idList"] = json!(c.id_list);
+                    card["pos"] = json!(c.pos);
+                    card
+                })
+                .collect();
+            board["cards"] = Value::Array(cards);
+        }
+        if let Some(filter) = params.get("labels") {
+            board["labels"] = match filter.as_str() {
+                "all" => to_json(&self.labels),
+                _ => json!([]),
+            };
+        }
+        if params.contains_key("members") {
+            board["members"] = to_json(&self.members);
+        }
+        board
+    }
+
+    /// Comments of a card, paged with the `before` and `limit` parameters as done by Trello
+    fn comments_of(&self, card_id: &str, params: &HashMap<String, String>) -> Result<Vec<Comment>> {
+        let comments = self.comments.get(card_id).map(Vec::as_slice).unwrap_or(&[]);
+        let start = match params.get("before") {
+            Some(before) => comments
+                .iter()
+                .position(|c| &c.id == before)
+                .map_or(comments.len(), |i| i + 1),
+            None => 0,
+        };
+        let limit = match params.get("limit") {
+            Some(limit) => limit
+                .parse()
+                .map_err(|_| TrelloError::Offline(format!("Invalid limit {}", limit)))?,
+            None => 50,
+        };
+
+        Ok(comments.iter().skip(start).take(limit).cloned().collect())
+    }
+
+    /// Cards of a list matching the given filter (open by default), in board order
+    fn cards_of(&self, list_id: &str, filter: Option<&String>) -> Vec<Card> {
+        self.cards
+            .iter()
+            .filter(|c| c.id_list == list_id && matches_filter(c.card.closed, filter, "open"))
+            .map(|c| c.card.clone())
+            .collect()
+    }
+
+    /// Supports plain search terms along with the `is:open`, `is:archived` and
+    /// `label:` operators. Terms match words in the name or description of a card,
+    /// or the start of a word when searching partially.
+    fn search(&self, params: &HashMap<String, String>) -> Result<Value> {
+        let query = params.get("query").map(|q| q.as_str()).unwrap_or_default();
+        let partial = params.get("partial").map(|p| p == "true").unwrap_or(false);
+        let limit = match params.get("cards_limit") {
+            Some(limit) => limit
+                .parse()
+                .map_err(|_| TrelloError::Offline(format!("Invalid cards limit {}", limit)))?,
+            None => 10,
+        };
+
+        let mut closed = None;
+        let mut label_names = vec![];
+        let mut terms = vec![];
+        for word in query.split_whitespace().map(|w| w.to_lowercase()) {
+            match word.split_once(':') {
+                Some(("is", "open")) => closed = Some(false),
+                Some(("is", "archived")) => closed = Some(true),
+                Some(("label", label)) => label_names.push(label.trim_matches('"').to_string()),
+                Some((operator, _)) => {
+                    return Err(TrelloError::Offline(format!(
+                        "The '{}' search operator is not supported when reading from an export",
+                        operator
+                    )));
+                }
+                None => terms.push(word),
+            }
+        }
+
+        let cards = self
+            .cards
+            .iter()
+            .map(|c| &c.card)
+            .filter(|c| closed.is_none_or(|closed| c.closed == closed))
+            .filter(|c| {
+                let labels = c.labels.iter().flatten().collect::<Vec<&Label>>();
+                label_names.iter().all(|name| {
+                    labels.iter().any(|l| {
+                        &l.name.to_lowercase() == name
+                            || l.color.as_ref().is_some_and(|c| c.as_str() == name)
+                    })
+                })
+            })
+            .filter(|c| {
+                let text = format!("{} {}", c.name, c.desc).to_lowercase();
+                let words = text
+                    .split(|ch: char| !ch.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .collect::<Vec<&str>>();
+                terms.iter().all(|term| {
+                    words.iter().any(|w| match partial {
+                        true => w.starts_with(term.as_str()),
+                        false => w == term,
+                    })
+                })
+            })
+            .take(limit)
+            .cloned()
+            .collect::<Vec<Card>>();
+
+        Ok(json!({ "cards": cards, "boards": [] }))
+    }
+}
@@ -0,0 +1,201 @@
+use crate::client::ClientConfig;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Name of the profile used when no other profile is selected or set as the default
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Named configurations of TrelloClients, e.g. one for a personal and one for a
+/// company account, as stored in the configuration file of tro:
+/// ```toml
+/// default_profile = "work"
+///
+/// [profiles.personal]
+/// key = "..."
+/// token = "..."
+///
+/// [profiles.work]
+/// key = "..."
+/// token = "..."
+/// ```
+/// Configuration files written before profiles were supported contain a single
+/// configuration at the top level, which is read as the "default" profile.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct Profiles {
+    /// Profile used when none is selected with the `TRO_PROFILE` environment variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ClientConfig>,
+}
+
+impl Profiles {
+    pub fn from_toml(contents: &str) -> Result<Profiles> {
+        let value: toml::Value = toml::from_str(contents)?;
+
+        if value.get("profiles").is_some() {
+            return Ok(value.try_into()?);
+        }
+        let mut profiles = BTreeMap::new();
+        profiles.insert(String::from(DEFAULT_PROFILE), value.try_into()?);
+
+        Ok(Profiles {
+            default_profile: None,
+            profiles,
+        })
+    }
+
+    /// Loads the profiles stored at the given path. No profiles are returned when
+    /// the file does not exist.
+    pub fn load(path: &Path) -> Result<Profiles> {
+        debug!("Loading configuration from {:?}", path);
+        match fs::read_to_string(path) {
+            Ok(contents) => Profiles::from_toml(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Profiles::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Saves the profiles to the given path. As the file may contain credentials,
+    /// it is only readable and writable by its owner.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        debug!("Saving configuration to {:?}", path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // converted into a Value first, which writes plain values ahead of tables
+        // regardless of the order in which fields are declared
+        let contents = toml::to_string(&toml::Value::try_from(self)?)?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // the mode only applies to new files
+            if path.exists() {
+                fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        options.open(path)?.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Whether other users are able to read the file at the given path
+    #[cfg(unix)]
+    pub fn is_world_readable(path: &Path) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o004 != 0)
+    }
+
+    /// Always false on platforms without unix permissions
+    #[cfg(not(unix))]
+    pub fn is_world_readable(_path: &Path) -> bool {
+        false
+    }
+
+    /// Fails unless the name only contains letters, digits, `-` and `_`. Names are
+    /// used as directory names, e.g. for the cache of each profile, so they must
+    /// not contain path separators or refer to parent directories.
+    pub fn validate_name(name: &str) -> Result<()> {
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if name.is_empty() || !valid {
+            return Err(format!(
+                "Invalid profile name '{}'. Profile names may only contain letters, digits, '-' and '_'",
+                name
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Name of the profile to use: the given name if any, otherwise the one set
+    /// with the `TRO_PROFILE` environment variable, otherwise the default profile
+    pub fn resolve(&self, name: Option<&str>) -> Result<String> {
+        let name = name
+            .map(String::from)
+            .or_else(|| env::var("TRO_PROFILE").ok().filter(|p| !p.is_empty()))
+            .or_else(|| self.default_profile.clone())
+            .unwrap_or_else(|| String::from(DEFAULT_PROFILE));
+        Profiles::validate_name(&name)?;
+
+        Ok(name)
+    }
+
+    /// Takes the configuration of the profile with the given name (resolved as
+    /// with `resolve`) out of the profiles
+    pub fn take(&mut self, name: Option<&str>) -> Result<ClientConfig> {
+        let name = self.resolve(name)?;
+        let mut config = self.profiles.remove(&name).ok_or_else(|| {
+            format!(
+                "Profile '{}' not found. Available profiles: {}",
+                name,
+                self.profiles
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })?;
+        config.profile = Some(name);
+
+        Ok(config)
+    }
+
+    /// Adds or replaces the configuration of a profile. The first profile which is
+    /// added becomes the default profile.
+    pub fn insert(&mut self, name: &str, config: ClientConfig) -> Result<()> {
+        Profiles::validate_name(name)?;
+        if self.profiles.is_empty() && name != DEFAULT_PROFILE {
+            self.default_profile = Some(String::from(name));
+        }
+        self.profiles.insert(String::from(name), config);
+
+        Ok(())
+    }
+
+    /// Sets the API key and token of a profile, keeping the rest of its
+    /// configuration, or adds a profile with them when it does not exist yet.
+    /// Credentials which are read from a command are not stored.
+    pub fn set_credentials(&mut self, name: &str, key: &str, token: &str) -> Result<()> {
+        let config = match self.profiles.get_mut(name) {
+            Some(config) => config,
+            None => {
+                let config = ClientConfig::new(&ClientConfig::default_host(), token, key);
+                return self.insert(name, config);
+            }
+        };
+        if config.key_command.is_none() {
+            config.key = String::from(key);
+        }
+        if config.token_command.is_none() {
+            config.token = String::from(token);
+        }
+
+        Ok(())
+    }
+
+    /// Makes the profile with the given name the default profile
+    pub fn set_default(&mut self, name: &str) -> Result<()> {
+        if !self.profiles.contains_key(name) {
+            return Err(format!("Profile '{}' not found", name).into());
+        }
+        self.default_profile = Some(String::from(name));
+
+        Ok(())
+    }
+}
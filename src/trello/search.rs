@@ -4,7 +4,7 @@ use super::client::TrelloClient;
 use super::trello_error::TrelloError;
 use super::trello_object::TrelloObject;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 type Result<T> = std::result::Result<T, TrelloError>;
 
@@ -15,7 +15,7 @@ pub struct SearchOptions {
     pub boards_limit: Option<i32>,
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq, Clone)]
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchResult {
     #[serde(default = "Vec::new")]
@@ -57,5 +57,5 @@ pub fn search(
 
     let url = client.config.get_trello_url("/1/search/", &params)?;
 
-    Ok(client.client.get(url).send()?.error_for_status()?.json()?)
+    Ok(client.send(client.client.get(url))?.json()?)
 }
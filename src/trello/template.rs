@@ -0,0 +1,224 @@
+use crate::board::Board;
+use crate::card::Card;
+use crate::client::TrelloClient;
+use crate::label::{Label, LabelColor};
+use crate::list::List;
+use crate::trello_error::TrelloError;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+type Result<T> = std::result::Result<T, TrelloError>;
+
+/// Declarative description of a board, usually written as a TOML file:
+/// ```toml
+/// name = "Website"
+///
+/// [[labels]]
+/// name = "Bug"
+/// color = "red"
+///
+/// [[lists]]
+/// name = "Backlog"
+///
+/// [[lists.cards]]
+/// name = "Write the about page"
+/// desc = "Who we are and what we do"
+/// labels = ["Bug"]
+///
+/// [[lists]]
+/// name = "Done"
+/// ```
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct BoardTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub labels: Vec<LabelTemplate>,
+    #[serde(default)]
+    pub lists: Vec<ListTemplate>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct LabelTemplate {
+    #[serde(default)]
+    pub name: String,
+    /// Labels without a color may leave it out
+    #[serde(default)]
+    pub color: Option<LabelColor>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct ListTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub cards: Vec<CardTemplate>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct CardTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    /// Names of labels declared in the template
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// The objects created when applying a template. Objects which already
+/// existed are not included.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct AppliedTemplate {
+    pub board: Board,
+    pub board_created: bool,
+    pub labels: Vec<Label>,
+    pub lists: Vec<List>,
+    pub cards: Vec<Card>,
+}
+
+impl AppliedTemplate {
+    pub fn is_empty(&self) -> bool {
+        !self.board_created
+            && self.labels.is_empty()
+            && self.lists.is_empty()
+            && self.cards.is_empty()
+    }
+}
+
+impl FromStr for BoardTemplate {
+    type Err = TrelloError;
+
+    /// Parses a template from TOML, checking that label colors are valid and that every
+    /// label used by a card is declared
+    /// ```
+    /// let result = "
+    /// name = 'Groceries'
+    /// [[lists]]
+    /// name = 'Fruit'
+    /// [[lists.cards]]
+    /// name = 'Apples'
+    /// labels = ['Organic']
+    /// ".parse::<trello::BoardTemplate>();
+    ///
+    /// assert!(result.is_err(), "The Organic label is not declared");
+    /// ```
+    fn from_str(value: &str) -> Result<BoardTemplate> {
+        let template: BoardTemplate = toml::from_str(value)
+            .map_err(|e| TrelloError::Template(format!("Invalid template: {}", e)))?;
+
+        for label in &template.labels {
+            if let Some(color) = &label.color {
+                color
+                    .as_str()
+                    .parse::<LabelColor>()
+                    .map_err(|e| TrelloError::Template(format!("Label '{}': {}", label.name, e)))?;
+            }
+        }
+
+        let label_names = template
+            .labels
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect::<HashSet<&str>>();
+
+        for list in &template.lists {
+            for card in &list.cards {
+                if let Some(name) = card
+                    .labels
+                    .iter()
+                    .find(|n| !label_names.contains(n.as_str()))
+                {
+                    return Err(TrelloError::Template(format!(
+                        "Label '{}' of card '{}' is not declared in the template",
+                        name, card.name
+                    )));
+                }
+            }
+        }
+        Ok(template)
+    }
+}
+
+impl BoardTemplate {
+    /// Creates the board described by the template, or completes it if an open board
+    /// with the same name already exists. Only what is missing is created: objects are
+    /// matched by their exact name (unnamed labels by their color) and existing objects
+    /// are left untouched, so applying a template again has no effect. Lists which are
+    /// missing from an existing board are added at the end of it.
+    pub fn apply(&self, client: &TrelloClient, default_lists: bool) -> Result<AppliedTemplate> {
+        let existing = Board::get_all(client)?
+            .into_iter()
+            .find(|b| b.name == self.name);
+
+        let (board, board_created) = match existing {
+            Some(board) => (board, false),
+            None => (
+                Board::create_with_defaults(client, &self.name, default_lists, true)?,
+                true,
+            ),
+        };
+
+        let mut board_labels = Label::get_all(client, &board.id)?;
+        let mut labels = vec![];
+        for template in &self.labels {
+            let exists = board_labels.iter().any(|l| match template.name.is_empty() {
+                true => l.name.is_empty() && l.color == template.color,
+                false => l.name == template.name,
+            });
+            if !exists {
+                debug!("Creating label {:?}", template);
+                let label =
+                    Label::create(client, &board.id, &template.name, template.color.as_ref())?;
+                board_labels.push(label.clone());
+                labels.push(label);
+            }
+        }
+
+        let board_lists = List::get_all(client, &board.id, true)?;
+        let mut lists = vec![];
+        let mut cards = vec![];
+        for template in &self.lists {
+            let (list, existing_cards) = match board_lists.iter().find(|l| l.name == template.name)
+            {
+                Some(list) => (list.clone(), list.cards.clone().unwrap_or_default()),
+                None => {
+                    debug!("Creating list {}", template.name);
+                    let list = List::create(client, &board.id, &template.name)?;
+                    lists.push(list.clone());
+                    (list, vec![])
+                }
+            };
+
+            for card_template in &template.cards {
+                if existing_cards.iter().any(|c| c.name == card_template.name) {
+                    continue;
+                }
+                debug!("Creating card {}", card_template.name);
+                let card = Card::new("", &card_template.name, &card_template.desc, None, "", None);
+                let mut card = Card::create(client, &list.id, &card)?;
+
+                let mut card_labels = vec![];
+                for name in &card_template.labels {
+                    let label = board_labels
+                        .iter()
+                        .find(|l| &l.name == name)
+                        .ok_or_else(|| {
+                            TrelloError::Template(format!("Label '{}' not found on board", name))
+                        })?;
+                    Label::apply(client, &card.id, &label.id)?;
+                    card_labels.push(label.clone());
+                }
+                card.labels = Some(card_labels);
+                cards.push(card);
+            }
+        }
+
+        Ok(AppliedTemplate {
+            board,
+            board_created,
+            labels,
+            lists,
+            cards,
+        })
+    }
+}
@@ -1,15 +1,29 @@
 mod test_attachment;
+mod test_authorize;
 mod test_board;
+mod test_cache;
 mod test_card;
+mod test_cassette;
+mod test_checklist;
+mod test_client;
+mod test_comment;
+mod test_custom_field;
+mod test_export;
+mod test_fake;
 mod test_formatting;
 mod test_label;
 mod test_list;
+mod test_member;
+mod test_offline;
+mod test_profiles;
 mod test_search;
+mod test_template;
+mod test_trello_error;
 
 use super::*;
-use mockito;
 use serde_json::json;
 use std::io::Write;
+use std::path::Path;
 use tempfile::NamedTempFile;
 
 type Result<T> = std::result::Result<T, TrelloError>;
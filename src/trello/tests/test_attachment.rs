@@ -1,10 +1,23 @@
 use super::*;
+use chrono::{TimeZone, Utc};
+
+fn attachment(id: &str, name: &str, url: &str) -> Attachment {
+    Attachment {
+        id: String::from(id),
+        name: String::from(name),
+        url: String::from(url),
+        bytes: None,
+        mime_type: None,
+        date: None,
+        is_upload: false,
+    }
+}
 
 #[test]
 fn test_get_all() -> Result<()> {
     let _m = mockito::mock(
         "GET",
-        "/1/cards/FOO-CARD/attachments?fields=id%2Cname%2Curl",
+        "/1/cards/FOO-CARD/attachments?fields=id%2Cname%2Curl%2Cbytes%2CmimeType%2Cdate%2CisUpload",
     )
     .match_header(
         "authorization",
@@ -12,11 +25,26 @@ fn test_get_all() -> Result<()> {
     )
     .with_status(200)
     .with_body(
-        json!([{
-            "name": "IMG_2000.png",
-            "id": "0012310",
-            "url": "https://example.com/1/12/IMG_2000.png",
-        }])
+        json!([
+            {
+                "name": "IMG_2000.png",
+                "id": "0012310",
+                "url": "https://example.com/1/12/IMG_2000.png",
+                "bytes": 2048,
+                "mimeType": "image/png",
+                "date": "2020-06-28T09:30:00.000Z",
+                "isUpload": true,
+            },
+            {
+                "name": "Example",
+                "id": "0012311",
+                "url": "https://example.com",
+                "bytes": null,
+                "mimeType": "",
+                "date": "2020-06-29T10:00:00.000Z",
+                "isUpload": false,
+            }
+        ])
         .to_string(),
     )
     .create();
@@ -26,11 +54,24 @@ fn test_get_all() -> Result<()> {
 
     let result = Attachment::get_all(&client, "FOO-CARD")?;
 
-    let expected = [Attachment {
-        id: String::from("0012310"),
-        name: String::from("IMG_2000.png"),
-        url: String::from("https://example.com/1/12/IMG_2000.png"),
-    }];
+    let expected = [
+        Attachment {
+            bytes: Some(2048),
+            mime_type: Some(String::from("image/png")),
+            date: Some(Utc.with_ymd_and_hms(2020, 6, 28, 9, 30, 0).unwrap()),
+            is_upload: true,
+            ..attachment(
+                "0012310",
+                "IMG_2000.png",
+                "https://example.com/1/12/IMG_2000.png",
+            )
+        },
+        Attachment {
+            mime_type: Some(String::from("")),
+            date: Some(Utc.with_ymd_and_hms(2020, 6, 29, 10, 0, 0).unwrap()),
+            ..attachment("0012311", "Example", "https://example.com")
+        },
+    ];
 
     assert_eq!(result, expected);
 
@@ -67,12 +108,159 @@ fn test_apply() -> Result<()> {
 
     assert_eq!(
         result,
-        Attachment {
-            id: String::from("my-attachment"),
-            name: String::from("My Attachment"),
-            url: String::from("https://some-example.com/attachment.txt"),
-        }
+        attachment(
+            "my-attachment",
+            "My Attachment",
+            "https://some-example.com/attachment.txt"
+        )
     );
 
     Ok(())
 }
+
+#[test]
+fn test_file_name() {
+    let upload = attachment(
+        "ATTACHMENT-1",
+        "My Report.pdf",
+        "https://trello.com/1/cards/CARD-1/attachments/ATTACHMENT-1/download/My_Report.pdf",
+    );
+    assert_eq!(upload.file_name(), Some("My_Report.pdf"));
+
+    let link = attachment("ATTACHMENT-2", "Example", "https://example.com/");
+    assert_eq!(link.file_name(), None);
+}
+
+#[test]
+fn test_download() -> Result<()> {
+    let _m = mockito::mock(
+        "GET",
+        "/1/cards/CARD-1/attachments/ATTACHMENT-1/download/notes%20final.txt",
+    )
+    .match_header(
+        "authorization",
+        "OAuth oauth_consumer_key=\"KEY\", oauth_token=\"TOKEN\"",
+    )
+    .with_status(200)
+    .with_body("some notes")
+    .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "TOKEN", "KEY");
+    let client = TrelloClient::new(config);
+
+    let attachment = attachment(
+        "ATTACHMENT-1",
+        "notes final.txt",
+        "https://trello.com/1/cards/CARD-1/attachments/ATTACHMENT-1/download/notes%20final.txt",
+    );
+    let mut contents = vec![];
+    let bytes = Attachment::download(&client, "CARD-1", &attachment, &mut contents)?;
+
+    assert_eq!(bytes, 10);
+    assert_eq!(contents, b"some notes");
+    Ok(())
+}
+
+#[test]
+fn test_download_link() {
+    let config = ClientConfig::new(&mockito::server_url(), "TOKEN", "KEY");
+    let client = TrelloClient::new(config);
+
+    let attachment = attachment("ATTACHMENT-2", "Example", "https://example.com/");
+    let error = Attachment::download(&client, "CARD-1", &attachment, &mut vec![])
+        .expect_err("Expected error");
+
+    assert!(matches!(error, TrelloError::Attachment(_)));
+}
+
+#[test]
+fn test_upload() -> Result<()> {
+    let _m = mockito::mock("POST", "/1/cards/CARD-24/attachments")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"KEY\", oauth_token=\"TOKEN\"",
+        )
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex(String::from(r#"filename="notes.txt""#)),
+            mockito::Matcher::Regex(String::from("some notes")),
+        ]))
+        .with_status(200)
+        .with_body(
+            json!({
+                "id": "ATTACHMENT-3",
+                "name": "notes.txt",
+                "url": "https://trello.com/1/cards/CARD-24/attachments/ATTACHMENT-3/download/notes.txt",
+            })
+            .to_string(),
+        )
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "TOKEN", "KEY");
+    let client = TrelloClient::new(config);
+
+    let result = Attachment::upload(&client, "CARD-24", "notes.txt", "some notes".as_bytes())?;
+
+    assert_eq!(result.name, "notes.txt");
+    Ok(())
+}
+
+#[test]
+fn test_delete() -> Result<()> {
+    let _m = mockito::mock("DELETE", "/1/cards/CARD-25/attachments/ATTACHMENT-4")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"KEY\", oauth_token=\"TOKEN\"",
+        )
+        .with_status(200)
+        .with_body("{}")
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "TOKEN", "KEY");
+    let client = TrelloClient::new(config);
+
+    Attachment::delete(&client, "CARD-25", "ATTACHMENT-4")?;
+    Ok(())
+}
+
+#[test]
+fn test_render_size() {
+    let size = |bytes| {
+        Attachment {
+            bytes,
+            ..attachment("", "", "")
+        }
+        .render_size()
+    };
+
+    assert_eq!(size(None), "-");
+    assert_eq!(size(Some(512)), "512 B");
+    assert_eq!(size(Some(1536)), "1.5 KB");
+    assert_eq!(size(Some(5 * 1024 * 1024)), "5.0 MB");
+    assert_eq!(size(Some(3 * 1024 * 1024 * 1024 * 1024)), "3072.0 GB");
+}
+
+#[test]
+fn test_render_table() {
+    let attachments = [
+        Attachment {
+            bytes: Some(2048),
+            mime_type: Some(String::from("image/png")),
+            is_upload: true,
+            ..attachment("1", "photo.png", "https://example.com/photo.png")
+        },
+        Attachment {
+            mime_type: Some(String::from("")),
+            ..attachment("2", "Example", "https://example.com")
+        },
+    ];
+
+    assert_eq!(
+        Attachment::render_table(&attachments),
+        [
+            "Name         Size  Type       Date  Url",
+            "photo.png  2.0 KB  image/png  -     https://example.com/photo.png",
+            "Example         -  link       -     https://example.com",
+        ]
+        .join("\n")
+    );
+}
@@ -0,0 +1,154 @@
+use super::*;
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+/// Waits for a token in the background, while the test stands in for the
+/// browser which Trello redirects to the listener
+fn listen() -> Result<(String, thread::JoinHandle<Result<String>>)> {
+    let listener = LoopbackListener::bind(0)?;
+    let return_url = listener.return_url()?;
+    let handle = thread::spawn(move || listener.wait_for_token(Duration::from_secs(10)));
+
+    Ok((return_url, handle))
+}
+
+/// Posts the fragment of the redirect to the given url, as the redirect page does
+fn post(url: &str, fragment: &str) -> Result<reqwest::blocking::Response> {
+    Ok(reqwest::blocking::Client::new()
+        .post(url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(String::from(fragment))
+        .send()?)
+}
+
+#[test]
+fn test_authorize_url() -> Result<()> {
+    let options = AuthorizeOptions {
+        app_name: String::from("tro work"),
+        scope: String::from("read"),
+        expiration: String::from("30days"),
+    };
+    let url = options.url("some-key", "http://127.0.0.1:4242/")?;
+
+    let params = url
+        .query_pairs()
+        .into_owned()
+        .collect::<std::collections::HashMap<String, String>>();
+    assert_eq!(url.host_str(), Some("trello.com"));
+    assert_eq!(url.path(), "/1/authorize");
+    assert_eq!(params["key"], "some-key");
+    assert_eq!(params["name"], "tro work");
+    assert_eq!(params["scope"], "read");
+    assert_eq!(params["expiration"], "30days");
+    assert_eq!(params["response_type"], "token");
+    assert_eq!(params["return_url"], "http://127.0.0.1:4242/");
+    Ok(())
+}
+
+#[test]
+fn test_captures_token() -> Result<()> {
+    let (return_url, handle) = listen()?;
+    let url = Url::parse(&return_url)?;
+    assert_eq!(url.host_str(), Some("127.0.0.1"));
+    assert_eq!(url.path().len(), "/".len() + 32 + "/".len());
+
+    // Trello redirects to the return url with the token in the fragment, which
+    // is never sent, so the listener answers with a page which posts it
+    let page = reqwest::blocking::get(format!("{}#token=some-token", return_url))?;
+    assert_eq!(page.status(), 200);
+    let text = page.text()?;
+    assert!(text.contains("window.location.hash"));
+    assert!(text.contains("history.replaceState"));
+
+    let favicon = reqwest::blocking::get(format!("{}favicon.ico", return_url))?;
+    assert_eq!(favicon.status(), 404);
+
+    // tokens are only read from the body, so that they never appear in a url
+    let page = reqwest::blocking::get(format!("{}?token=other-token", return_url))?;
+    assert!(!page.text()?.contains("tro has been authorized"));
+
+    let page = post(&return_url, "token=some-token")?;
+    assert_eq!(page.status(), 200);
+    assert!(page.text()?.contains("tro has been authorized"));
+
+    let token = handle.join().expect("Listener panicked")?;
+    assert_eq!(token, "some-token");
+    Ok(())
+}
+
+#[test]
+fn test_return_url_is_unique() -> Result<()> {
+    let first = LoopbackListener::bind(0)?;
+    let second = LoopbackListener::bind(0)?;
+
+    let path = |l: &LoopbackListener| -> Result<String> {
+        Ok(Url::parse(&l.return_url()?)?.path().to_string())
+    };
+    assert_ne!(path(&first)?, path(&second)?);
+    Ok(())
+}
+
+#[test]
+fn test_wrong_path_is_rejected() -> Result<()> {
+    let (return_url, handle) = listen()?;
+    let url = Url::parse(&return_url)?;
+
+    for path in ["/", "/wrong-nonce/"] {
+        let page = post(url.join(path)?.as_str(), "token=other-token")?;
+        assert_eq!(page.status(), 404);
+    }
+
+    // the listener keeps waiting for the token on the return url
+    post(&return_url, "token=some-token")?;
+    let token = handle.join().expect("Listener panicked")?;
+    assert_eq!(token, "some-token");
+    Ok(())
+}
+
+#[test]
+fn test_idle_connection_is_ignored() -> Result<()> {
+    let (return_url, handle) = listen()?;
+    let url = Url::parse(&return_url)?;
+
+    // browsers open connections which never send a request
+    let idle = TcpStream::connect((url.host_str().unwrap_or_default(), url.port().unwrap_or(80)))?;
+    let broken =
+        TcpStream::connect((url.host_str().unwrap_or_default(), url.port().unwrap_or(80)))?;
+    drop(broken);
+
+    let page = post(&return_url, "token=some-token")?;
+    assert_eq!(page.status(), 200);
+
+    let token = handle.join().expect("Listener panicked")?;
+    assert_eq!(token, "some-token");
+    drop(idle);
+    Ok(())
+}
+
+#[test]
+fn test_denied() -> Result<()> {
+    let (return_url, handle) = listen()?;
+
+    let page = post(&return_url, "error=Token%20request%20rejected")?;
+    assert!(page.text()?.contains("tro was not authorized"));
+
+    let error = handle
+        .join()
+        .expect("Listener panicked")
+        .expect_err("Expected an error");
+    assert!(matches!(error, TrelloError::Authorize(m) if m.contains("Token request rejected")));
+    Ok(())
+}
+
+#[test]
+fn test_timeout() -> Result<()> {
+    let listener = LoopbackListener::bind(0)?;
+
+    let error = listener
+        .wait_for_token(Duration::from_millis(100))
+        .expect_err("Expected an error");
+    assert!(matches!(error, TrelloError::Authorize(_)));
+    Ok(())
+}
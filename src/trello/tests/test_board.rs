@@ -9,6 +9,7 @@ fn test_new() {
         id: String::from("888"),
         name: String::from("some board"),
         lists: Some(vec![]),
+        labels: None,
         closed: false,
         url: String::from("https://trello.com/09"),
     };
@@ -234,3 +235,77 @@ fn test_get() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_retrieve_nested() -> Result<()> {
+    let fake = FakeTrello::new();
+    let setup = fake.client();
+    let board = Board::create(&setup, "Garden")?;
+    let lists = List::get_all(&setup, &board.id, false)?;
+    Label::create(&setup, &board.id, "Urgent", None)?;
+
+    let card = Card::create(
+        &setup,
+        &lists[1].id,
+        &Card::new("", "Rake", "", None, "", None),
+    )?;
+    Card::add_member(&setup, &card.id, &Member::me(&setup)?.id)?;
+    Card::create(
+        &setup,
+        &lists[1].id,
+        &Card::new("", "Mow", "", None, "", None),
+    )?;
+
+    let client = fake.client();
+    let mut board = Board::get(&client, &board.id)?;
+    board.retrieve_nested(&client)?;
+    assert_eq!(client.request_count(), 2);
+
+    let lists = board.lists.expect("Lists were not retrieved");
+    let names = |list: &List| {
+        list.cards
+            .iter()
+            .flatten()
+            .map(|c| c.name.clone())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(lists.len(), 3);
+    assert!(names(&lists[0]).is_empty());
+    assert_eq!(names(&lists[1]), vec!["Rake", "Mow"]);
+
+    let rake = &lists[1].cards.as_ref().unwrap()[0];
+    assert_eq!(rake.members.as_ref().map(|m| m.len()), Some(1));
+
+    let labels = board.labels.expect("Labels were not retrieved");
+    assert!(labels.iter().any(|l| l.name == "Urgent"));
+    Ok(())
+}
+
+#[test]
+fn test_retrieve_structure() -> Result<()> {
+    let fake = FakeTrello::new();
+    let setup = fake.client();
+    let board = Board::create(&setup, "Garden")?;
+    let lists = List::get_all(&setup, &board.id, false)?;
+    Label::create(&setup, &board.id, "Urgent", None)?;
+    Card::create(
+        &setup,
+        &lists[0].id,
+        &Card::new("", "Rake", "", None, "", None),
+    )?;
+
+    let client = fake.client();
+    let mut board = Board::get(&client, &board.id)?;
+    board.retrieve_structure(&client)?;
+
+    let lists = board.lists.as_ref().expect("Lists were not retrieved");
+    assert_eq!(lists.len(), 3);
+    assert!(lists.iter().all(|l| l.cards.is_none()));
+    assert!(board.labels.iter().flatten().any(|l| l.name == "Urgent"));
+
+    // cards are retrieved once they are needed
+    board.retrieve_nested(&client)?;
+    let lists = board.lists.expect("Lists were not retrieved");
+    assert_eq!(lists[0].cards.as_ref().map(|c| c.len()), Some(1));
+    Ok(())
+}
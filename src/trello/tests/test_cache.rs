@@ -0,0 +1,167 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// Creates a client backed by the given fake which caches its responses in `dir`
+fn cached_client(fake: &FakeTrello, dir: &Path, refresh: bool) -> TrelloClient {
+    let mut config = ClientConfig::new(&ClientConfig::default_host(), "fake-token", "fake-key");
+    config.retry = RetryPolicy::none();
+    config.cache = Some(CacheConfig {
+        dir: Some(dir.to_path_buf()),
+        refresh,
+        ..CacheConfig::default()
+    });
+
+    TrelloClient::with_transport(config, fake.clone())
+}
+
+fn board_names(client: &TrelloClient) -> Result<Vec<String>> {
+    Ok(Board::get_all(client)?
+        .into_iter()
+        .map(|b| b.name)
+        .collect())
+}
+
+#[test]
+fn test_serves_cached_boards() -> Result<()> {
+    let dir = TempDir::new()?;
+    let fake = FakeTrello::new();
+    Board::create(&fake.client(), "First")?;
+
+    let client = cached_client(&fake, dir.path(), false);
+    assert_eq!(board_names(&client)?, vec!["First"]);
+    assert!(!client.served_from_cache());
+
+    // created without the cache, so the cached boards are now out of date
+    Board::create(&fake.client(), "Second")?;
+
+    let client = cached_client(&fake, dir.path(), false);
+    assert_eq!(board_names(&client)?, vec!["First"]);
+    assert!(client.served_from_cache());
+    assert_eq!(client.request_count(), 0);
+
+    client.invalidate_cache();
+    assert_eq!(board_names(&client)?, vec!["First", "Second"]);
+    assert!(!client.served_from_cache());
+    assert_eq!(client.request_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_refresh_ignores_cached_responses() -> Result<()> {
+    let dir = TempDir::new()?;
+    let fake = FakeTrello::new();
+    Board::create(&fake.client(), "First")?;
+
+    board_names(&cached_client(&fake, dir.path(), false))?;
+    Board::create(&fake.client(), "Second")?;
+
+    let client = cached_client(&fake, dir.path(), true);
+    assert_eq!(board_names(&client)?, vec!["First", "Second"]);
+    assert!(!client.served_from_cache());
+
+    // refreshed responses are cached for later clients
+    let client = cached_client(&fake, dir.path(), false);
+    assert_eq!(board_names(&client)?, vec!["First", "Second"]);
+    assert!(client.served_from_cache());
+    Ok(())
+}
+
+#[test]
+fn test_mutation_invalidates_cache() -> Result<()> {
+    let dir = TempDir::new()?;
+    let fake = FakeTrello::new();
+    let board = Board::create(&fake.client(), "Board")?;
+
+    let client = cached_client(&fake, dir.path(), false);
+    List::get_all(&client, &board.id, false)?;
+    assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+    List::create(&client, &board.id, "Extra")?;
+    assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+
+    let client = cached_client(&fake, dir.path(), false);
+    let lists = List::get_all(&client, &board.id, false)?;
+    assert!(lists.iter().any(|l| l.name == "Extra"));
+    assert!(!client.served_from_cache());
+    Ok(())
+}
+
+#[test]
+fn test_cards_are_not_cached() -> Result<()> {
+    let dir = TempDir::new()?;
+    let fake = FakeTrello::new();
+    let board = Board::create(&fake.client(), "Board")?;
+    let list = &List::get_all(&fake.client(), &board.id, false)?[0];
+
+    let client = cached_client(&fake, dir.path(), false);
+    List::get_all(&client, &board.id, true)?;
+    Card::create(
+        &fake.client(),
+        &list.id,
+        &Card::new("", "Fresh", "", None, "", None),
+    )?;
+
+    let client = cached_client(&fake, dir.path(), false);
+    let lists = List::get_all(&client, &board.id, true)?;
+    let cards = lists[0].cards.as_ref().expect("Cards were not retrieved");
+    assert_eq!(cards[0].name, "Fresh");
+    assert!(!client.served_from_cache());
+    Ok(())
+}
+
+#[test]
+fn test_expired_responses_are_not_served() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("cache.json");
+    let fake = FakeTrello::new();
+    Board::create(&fake.client(), "First")?;
+
+    let config = CacheConfig {
+        ttl_secs: 0,
+        ..CacheConfig::default()
+    };
+    let client = TrelloClient::with_transport(
+        ClientConfig::new(&ClientConfig::default_host(), "fake-token", "fake-key"),
+        CachingTransport::new(Box::new(fake.clone()), &path, &config),
+    );
+    board_names(&client)?;
+    Board::create(&fake.client(), "Second")?;
+
+    assert_eq!(board_names(&client)?, vec!["First", "Second"]);
+    assert!(!client.served_from_cache());
+    Ok(())
+}
+
+#[test]
+fn test_cache_per_account() {
+    let dir = Path::new("/cache");
+    let host = ClientConfig::default_host();
+
+    let path = CacheConfig::path(dir, &host, "key", "token");
+    assert_eq!(path.parent(), Some(dir));
+    assert!(!path.to_string_lossy().contains("token"));
+
+    assert_eq!(path, CacheConfig::path(dir, &host, "key", "token"));
+    assert_ne!(path, CacheConfig::path(dir, &host, "key", "other-token"));
+    assert_ne!(path, CacheConfig::path(dir, &host, "other-key", "token"));
+
+    // file names must not change between releases, or cache files are orphaned
+    assert_eq!(path, dir.join("b39cef70f66f7a73348ae2e183e398d6.json"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cache_file_permissions() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new()?;
+    let fake = FakeTrello::new();
+    Board::create(&fake.client(), "First")?;
+
+    board_names(&cached_client(&fake, dir.path(), false))?;
+
+    let file = fs::read_dir(dir.path())?.next().expect("No cache file")?;
+    assert_eq!(file.metadata()?.permissions().mode() & 0o777, 0o600);
+    Ok(())
+}
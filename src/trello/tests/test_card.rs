@@ -1,5 +1,6 @@
 use super::*;
-use chrono::{TimeZone, Utc};
+use chrono::{Local, TimeZone, Utc};
+use colored::*;
 
 #[test]
 fn test_new() {
@@ -10,12 +11,55 @@ fn test_new() {
         desc: String::from("C"),
         labels: None,
         due: None,
+        due_complete: false,
         closed: false,
         url: String::from("https://trello.com/my/card"),
+        checklists: None,
+        id_members: vec![],
+        members: None,
+        id_board: String::new(),
+        id_short: None,
+        custom_field_items: None,
+        custom_fields: None,
     };
     assert_eq!(card, expected);
 }
 
+#[test]
+fn test_serialize_uses_trello_field_names() {
+    let mut card = Card::new(
+        "CARD-ID",
+        "Card",
+        "",
+        Some(vec![Label::new("LABEL-ID", "Urgent", "red")]),
+        "https://trello.com/c/1",
+        None,
+    );
+    card.id_members = vec![String::from("MEMBER-ID")];
+    card.id_board = String::from("BOARD-ID");
+
+    let value = serde_json::to_value(&card).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "id": "CARD-ID",
+            "name": "Card",
+            "desc": "",
+            "closed": false,
+            "url": "https://trello.com/c/1",
+            "labels": [{"id": "LABEL-ID", "name": "Urgent", "color": "red"}],
+            "due": null,
+            "dueComplete": false,
+            "idMembers": ["MEMBER-ID"],
+            "idBoard": "BOARD-ID",
+        })
+    );
+
+    let deserialized: Card = serde_json::from_value(value).unwrap();
+    assert_eq!(deserialized, card);
+}
+
 #[test]
 fn test_render() {
     let card = Card::new(
@@ -31,6 +75,111 @@ fn test_render() {
     assert_eq!(card.render(true), expected);
 }
 
+#[test]
+fn test_render_with_checklists() {
+    let mut card = Card::new("aaaaa", "Groceries", "for the weekend", None, "", None);
+    card.checklists = Some(vec![
+        Checklist::new(
+            "1",
+            "Dairy",
+            vec![
+                CheckItem::new("11", "milk", CheckItemState::Complete),
+                CheckItem::new("12", "cheese", CheckItemState::Incomplete),
+            ],
+        ),
+        Checklist::new("2", "Bakery", vec![]),
+    ]);
+
+    #[rustfmt::skip]
+    let expected = [
+        "Groceries",
+        "=========",
+        "for the weekend",
+        "",
+        "Checklist: Dairy",
+        "----------------",
+        "- [x] milk",
+        "- [ ] cheese",
+        "",
+        "Checklist: Bakery",
+        "-----------------",
+    ].join("\n");
+    assert_eq!(card.render(true), expected);
+}
+
+#[test]
+fn test_render_with_custom_fields() {
+    let mut card = Card::new("aaaaa", "Groceries", "for the weekend", None, "", None);
+    card.custom_fields = Some(vec![
+        CustomField::new("f1", "Points", CustomFieldType::Number, vec![]),
+        CustomField::new("f2", "Notes", CustomFieldType::Text, vec![]),
+    ]);
+    card.custom_field_items = Some(vec![CustomFieldItem {
+        id: String::from("i1"),
+        id_custom_field: String::from("f1"),
+        value: Some(CustomFieldItemValue {
+            number: Some(String::from("3")),
+            ..Default::default()
+        }),
+        id_value: None,
+    }]);
+
+    #[rustfmt::skip]
+    let expected = [
+        "Groceries",
+        "=========",
+        "for the weekend",
+        "",
+        "Custom Fields",
+        "-------------",
+        "Points: 3",
+    ].join("\n");
+    assert_eq!(card.render(true), expected);
+
+    let contents: CardContents = expected.parse().unwrap();
+    assert_eq!(contents.desc, "for the weekend");
+    assert_eq!(contents.checklists, vec![]);
+}
+
+#[test]
+fn test_card_contents_round_trip() -> Result<()> {
+    let mut card = Card::new("aaaaa", "Groceries", "for the\nweekend", None, "", None);
+    card.checklists = Some(vec![Checklist::new(
+        "1",
+        "Dairy",
+        vec![
+            CheckItem::new("11", "milk", CheckItemState::Complete),
+            CheckItem::new("12", "cheese", CheckItemState::Incomplete),
+        ],
+    )]);
+
+    let contents: CardContents = card.render(true).parse()?;
+
+    assert_eq!(contents.name, "Groceries");
+    assert_eq!(contents.desc, "for the\nweekend");
+    assert!(!Checklist::differs(
+        card.checklists.as_ref().unwrap(),
+        &contents.checklists
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_card_contents_no_checklists() -> Result<()> {
+    let contents: CardContents = "Title\n===\n- [ ] not a checklist\n".parse()?;
+
+    assert_eq!(contents.desc, "- [ ] not a checklist\n");
+    assert_eq!(contents.checklists, vec![]);
+    Ok(())
+}
+
+#[test]
+fn test_card_contents_invalid_checklist_item() {
+    let result = "Title\n===\n\nChecklist: Foo\n---\n* bad item".parse::<CardContents>();
+
+    assert!(matches!(result, Err(TrelloError::CardParse(_))));
+}
+
 #[test]
 fn test_simple_render() {
     let card = Card {
@@ -41,6 +190,14 @@ fn test_simple_render() {
         url: String::from(""),
         labels: None,
         due: None,
+        due_complete: false,
+        checklists: None,
+        id_members: vec![],
+        members: None,
+        id_board: String::new(),
+        id_short: None,
+        custom_field_items: None,
+        custom_fields: None,
     };
 
     let expected = "Fire Monkey";
@@ -57,6 +214,14 @@ fn test_simple_render_with_description() {
         url: String::from(""),
         labels: None,
         due: None,
+        due_complete: false,
+        checklists: None,
+        id_members: vec![],
+        members: None,
+        id_board: String::new(),
+        id_short: None,
+        custom_field_items: None,
+        custom_fields: None,
     };
 
     let expected = "Ice Snail \u{1b}[2m[...]\u{1b}[0m";
@@ -73,12 +238,66 @@ fn test_simple_render_with_labels() {
         url: String::from(""),
         labels: Some(vec![Label::new("", "Animals", "green")]),
         due: None,
+        due_complete: false,
+        checklists: None,
+        id_members: vec![],
+        members: None,
+        id_board: String::new(),
+        id_short: None,
+        custom_field_items: None,
+        custom_fields: None,
     };
 
     let expected = "Lightning Goat \u{1b}[48;2;97;189;79;37m Animals \u{1b}[0m";
     assert_eq!(card.simple_render(), expected);
 }
 
+#[test]
+fn test_simple_render_with_members() {
+    let mut card = Card::new("1234", "Water Beetle", "", None, "", None);
+    card.id_members = vec![String::from("m1")];
+    card.populate_members(&[
+        Member::new("m1", "King Knight", "king", "KK"),
+        Member::new("m2", "Shovel Knight", "shovel", "SK"),
+    ]);
+
+    let expected = "Water Beetle \u{1b}[36m[KK]\u{1b}[0m";
+    assert_eq!(card.simple_render(), expected);
+}
+
+#[test]
+fn test_simple_render_overdue() {
+    let due = Utc.with_ymd_and_hms(2020, 6, 28, 6, 6, 27).unwrap();
+    let card = Card::new("1234", "Wind Bat", "", None, "", Some(due));
+
+    let expected = format!(
+        "Wind Bat {}",
+        format!(
+            "[Due {}]",
+            due.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+        .red()
+    );
+    assert_eq!(card.simple_render(), expected);
+}
+
+#[test]
+fn test_simple_render_due_complete() {
+    let due = Utc.with_ymd_and_hms(2020, 6, 28, 6, 6, 27).unwrap();
+    let mut card = Card::new("1234", "Wind Bat", "", None, "", Some(due));
+    card.due_complete = true;
+
+    let expected = format!(
+        "Wind Bat {}",
+        format!(
+            "[Due {}]",
+            due.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+        .green()
+    );
+    assert_eq!(card.simple_render(), expected);
+}
+
 #[test]
 fn test_simple_render_closed() {
     let card = Card {
@@ -89,6 +308,14 @@ fn test_simple_render_closed() {
         url: String::from(""),
         labels: None,
         due: None,
+        due_complete: false,
+        checklists: None,
+        id_members: vec![],
+        members: None,
+        id_board: String::new(),
+        id_short: None,
+        custom_field_items: None,
+        custom_fields: None,
     };
 
     let expected = "\u{1b}[31m[Closed]\u{1b}[0m Earth Seagull";
@@ -219,7 +446,7 @@ fn test_update() -> Result<()> {
 fn test_get_all() -> Result<()> {
     let _m = mockito::mock(
         "GET",
-        "/1/lists/DEADBEEF/cards/?fields=id%2Cname%2Cdesc%2Clabels%2Cclosed%2Cdue%2Curl",
+        "/1/lists/DEADBEEF/cards/?fields=id%2Cname%2Cdesc%2Clabels%2Cclosed%2Cdue%2CdueComplete%2Curl%2CidMembers%2CidBoard%2CidShort",
     )
     .match_header(
         "authorization",
@@ -275,3 +502,107 @@ fn test_get_all() -> Result<()> {
     assert_eq!(result, expected);
     Ok(())
 }
+
+#[test]
+fn test_add_member() -> Result<()> {
+    let m = mockito::mock("POST", "/1/cards/SOME-CARD-ID/idMembers")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("value=MY-MEMBER-ID")
+        .with_status(200)
+        .with_body(json!([]).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    Card::add_member(&client, "SOME-CARD-ID", "MY-MEMBER-ID")?;
+
+    m.assert();
+    Ok(())
+}
+
+#[test]
+fn test_remove_member() -> Result<()> {
+    let m = mockito::mock("DELETE", "/1/cards/FOO-CARD/idMembers/BAR-MEMBER")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .with_status(200)
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    Card::remove_member(&client, "FOO-CARD", "BAR-MEMBER")?;
+
+    m.assert();
+    Ok(())
+}
+
+#[test]
+fn test_set_due() -> Result<()> {
+    let _m = mockito::mock("PUT", "/1/cards/MY-CARD-ID/")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("due=2020-06-28T06%3A06%3A27%2B00%3A00")
+        .with_status(200)
+        .with_body(
+            json!({
+                "name": "Laundry",
+                "desc": "",
+                "id": "MY-CARD-ID",
+                "closed": false,
+                "url": "",
+                "due": "2020-06-28T06:06:27.000Z",
+            })
+            .to_string(),
+        )
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let due = Utc.with_ymd_and_hms(2020, 6, 28, 6, 6, 27).single();
+    let result = Card::set_due(&client, "MY-CARD-ID", due)?;
+
+    assert_eq!(result.due, due);
+    Ok(())
+}
+
+#[test]
+fn test_clear_due() -> Result<()> {
+    let m = mockito::mock("PUT", "/1/cards/MY-CARD-ID/")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("due=null")
+        .with_status(200)
+        .with_body(
+            json!({
+                "name": "Laundry",
+                "desc": "",
+                "id": "MY-CARD-ID",
+                "closed": false,
+                "url": "",
+                "due": null,
+            })
+            .to_string(),
+        )
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Card::set_due(&client, "MY-CARD-ID", None)?;
+
+    m.assert();
+    assert_eq!(result.due, None);
+    Ok(())
+}
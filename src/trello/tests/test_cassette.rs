@@ -0,0 +1,159 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn config_with_cassette(host: &str, mode: CassetteMode, path: &Path) -> ClientConfig {
+    let mut config = ClientConfig::new(host, "secret-token", "secret-key");
+    config.retry = RetryPolicy::none();
+    config.cassette = Some(CassetteConfig::new(mode, path));
+    config
+}
+
+#[test]
+fn test_record_redacts_authorization() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("cassette.json");
+
+    let _m = mockito::mock("GET", "/1/boards/RECORD-ME?fields=id%2Cname%2Cclosed%2Curl")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"secret-key\", oauth_token=\"secret-token\"",
+        )
+        .with_status(200)
+        .with_body(
+            json!({"id": "RECORD-ME", "name": "Recorded", "closed": false, "url": ""}).to_string(),
+        )
+        .create();
+
+    let config = config_with_cassette(&mockito::server_url(), CassetteMode::Record, &path);
+    let client = TrelloClient::new(config);
+    let board = Board::get(&client, "RECORD-ME")?;
+    assert_eq!(board.name, "Recorded");
+
+    let contents = fs::read_to_string(&path)?;
+    assert!(!contents.contains("secret-token"));
+    assert!(!contents.contains("secret-key"));
+
+    let cassette = Cassette::load(&path)?;
+    assert_eq!(cassette.interactions.len(), 1);
+    let interaction = &cassette.interactions[0];
+    assert_eq!(interaction.request.method, "GET");
+    assert_eq!(interaction.request.headers["authorization"], "REDACTED");
+    assert_eq!(interaction.response.status, 200);
+    assert!(interaction.response.body.contains("Recorded"));
+    Ok(())
+}
+
+#[test]
+fn test_record_and_replay_binary_body() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("cassette.json");
+    let contents: Vec<u8> = vec![0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe, b'\n'];
+
+    let _m = mockito::mock(
+        "GET",
+        "/1/cards/CARD-1/attachments/ATTACHMENT-1/download/image.png",
+    )
+    .with_status(200)
+    .with_body(&contents)
+    .create();
+
+    let attachment: Attachment = serde_json::from_value(json!({
+        "id": "ATTACHMENT-1",
+        "name": "image.png",
+        "url": "https://trello.com/1/cards/CARD-1/attachments/ATTACHMENT-1/download/image.png",
+    }))
+    .unwrap();
+
+    let config = config_with_cassette(&mockito::server_url(), CassetteMode::Record, &path);
+    let mut recorded = vec![];
+    Attachment::download(
+        &TrelloClient::new(config),
+        "CARD-1",
+        &attachment,
+        &mut recorded,
+    )?;
+    assert_eq!(recorded, contents);
+
+    let cassette = Cassette::load(&path)?;
+    assert!(cassette.interactions[0].response.base64);
+
+    let config = config_with_cassette(&mockito::server_url(), CassetteMode::Replay, &path);
+    let mut replayed = vec![];
+    Attachment::download(
+        &TrelloClient::new(config),
+        "CARD-1",
+        &attachment,
+        &mut replayed,
+    )?;
+    assert_eq!(replayed, contents);
+    Ok(())
+}
+
+#[test]
+fn test_replay_unmatched_request() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("cassette.json");
+    Cassette::default().save(&path)?;
+
+    let config = config_with_cassette(&ClientConfig::default_host(), CassetteMode::Replay, &path);
+    let client = TrelloClient::new(config);
+
+    let error = Board::get(&client, "MISSING").expect_err("Expected an error");
+    assert!(matches!(error, TrelloError::Cassette(m) if m.contains("/1/boards/MISSING")));
+    Ok(())
+}
+
+#[test]
+fn test_replay_missing_cassette() {
+    let config = config_with_cassette(
+        &ClientConfig::default_host(),
+        CassetteMode::Replay,
+        Path::new("/does/not/exist.json"),
+    );
+    let client = TrelloClient::new(config);
+
+    let error = Board::get(&client, "MISSING").expect_err("Expected an error");
+    assert!(matches!(error, TrelloError::Io(_)));
+}
+
+/// Records a session covering every module against the fake backend, and then
+/// replays it without any backend at all
+#[test]
+fn test_record_and_replay_session() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("session.json");
+
+    let mut file = NamedTempFile::new()?;
+    write!(file, "attached contents")?;
+    let file_path = file.path().to_str().unwrap();
+
+    let session = |client: &TrelloClient| -> Result<Vec<String>> {
+        let board = Board::create(client, "Session")?;
+        let list = List::create(client, &board.id, "Backlog")?;
+        let card = Card::create(client, &list.id, &Card::new("", "Task", "", None, "", None))?;
+        let label = &Label::get_all(client, &board.id)?[0];
+        Label::apply(client, &card.id, &label.id)?;
+        let attachment = Attachment::apply(client, &card.id, file_path)?;
+        let found = search(client, "Task", &SearchOptions::default())?;
+        let lists = List::get_all(client, &board.id, true)?;
+
+        Ok(vec![
+            board.id,
+            card.id,
+            attachment.id,
+            format!("{:?}", found),
+            format!("{:?}", lists),
+        ])
+    };
+
+    let fake = FakeTrello::new();
+    let config = config_with_cassette(&ClientConfig::default_host(), CassetteMode::Record, &path);
+    let recorded = session(&TrelloClient::with_transport(config, fake))?;
+
+    let config = config_with_cassette(&ClientConfig::default_host(), CassetteMode::Replay, &path);
+    let replayed = session(&TrelloClient::new(config))?;
+
+    assert_eq!(recorded, replayed);
+    Ok(())
+}
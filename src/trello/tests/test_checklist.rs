@@ -0,0 +1,155 @@
+use super::*;
+
+#[test]
+fn test_simple_render() {
+    let checklist = Checklist::new(
+        "1",
+        "Packing",
+        vec![
+            CheckItem::new("11", "passport", CheckItemState::Complete),
+            CheckItem::new("12", "socks", CheckItemState::Incomplete),
+        ],
+    );
+
+    assert_eq!(checklist.simple_render(), "Packing (1/2)");
+}
+
+#[test]
+fn test_get_all() -> Result<()> {
+    let _m = mockito::mock(
+        "GET",
+        "/1/cards/CARD-1/checklists?fields=id%2Cname&checkItem_fields=id%2Cname%2Cstate",
+    )
+    .match_header(
+        "authorization",
+        "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+    )
+    .with_status(200)
+    .with_body(
+        json!([{
+            "id": "CL-1",
+            "name": "Packing",
+            "checkItems": [
+                {"id": "CI-1", "name": "passport", "state": "complete"},
+                {"id": "CI-2", "name": "socks", "state": "incomplete"},
+            ],
+        }])
+        .to_string(),
+    )
+    .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Checklist::get_all(&client, "CARD-1")?;
+    let expected = vec![Checklist::new(
+        "CL-1",
+        "Packing",
+        vec![
+            CheckItem::new("CI-1", "passport", CheckItemState::Complete),
+            CheckItem::new("CI-2", "socks", CheckItemState::Incomplete),
+        ],
+    )];
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_create() -> Result<()> {
+    let _m = mockito::mock("POST", "/1/cards/CARD-2/checklists")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("name=Shopping")
+        .with_status(200)
+        .with_body(json!({"id": "CL-2", "name": "Shopping", "checkItems": []}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Checklist::create(&client, "CARD-2", "Shopping")?;
+
+    assert_eq!(result, Checklist::new("CL-2", "Shopping", vec![]));
+    Ok(())
+}
+
+#[test]
+fn test_check() -> Result<()> {
+    let _m = mockito::mock("PUT", "/1/cards/CARD-3/checkItem/CI-9")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("state=complete")
+        .with_status(200)
+        .with_body(json!({"id": "CI-9", "name": "eggs", "state": "complete"}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = CheckItem::check(&client, "CARD-3", "CI-9")?;
+
+    assert_eq!(
+        result,
+        CheckItem::new("CI-9", "eggs", CheckItemState::Complete)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sync() -> Result<()> {
+    let auth = "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"";
+
+    let rename = mockito::mock("PUT", "/1/cards/CARD-4/checkItem/CI-2")
+        .match_header("authorization", auth)
+        .match_body("name=woolly+socks&state=complete")
+        .with_status(200)
+        .with_body(json!({"id": "CI-2", "name": "woolly socks", "state": "complete"}).to_string())
+        .create();
+    let delete = mockito::mock("DELETE", "/1/checklists/CL-2")
+        .match_header("authorization", auth)
+        .with_status(200)
+        .create();
+    let refresh = mockito::mock(
+        "GET",
+        "/1/cards/CARD-4/checklists?fields=id%2Cname&checkItem_fields=id%2Cname%2Cstate",
+    )
+    .match_header("authorization", auth)
+    .with_status(200)
+    .with_body(json!([]).to_string())
+    .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let current = vec![
+        Checklist::new(
+            "CL-1",
+            "Packing",
+            vec![
+                CheckItem::new("CI-1", "passport", CheckItemState::Complete),
+                CheckItem::new("CI-2", "socks", CheckItemState::Incomplete),
+            ],
+        ),
+        Checklist::new("CL-2", "Old", vec![]),
+    ];
+    let desired = vec![Checklist::new(
+        "",
+        "Packing",
+        vec![
+            CheckItem::new("", "passport", CheckItemState::Complete),
+            CheckItem::new("", "woolly socks", CheckItemState::Complete),
+        ],
+    )];
+
+    Checklist::sync(&client, "CARD-4", &current, &desired)?;
+
+    rename.assert();
+    delete.assert();
+    refresh.assert();
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use super::*;
+use std::time::{Duration, Instant};
+
+fn client_with_retry(retry: RetryPolicy) -> TrelloClient {
+    let mut config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    config.retry = retry;
+    TrelloClient::new(config)
+}
+
+fn fast_retry(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay_ms: 1,
+        max_delay_ms: 1,
+        honour_retry_after: false,
+    }
+}
+
+fn board_path(board_id: &str) -> String {
+    format!("/1/boards/{}?fields=id%2Cname%2Cclosed%2Curl", board_id)
+}
+
+#[test]
+fn test_retry_server_error() -> Result<()> {
+    let path = board_path("RETRY-500");
+    let failure = mockito::mock("GET", path.as_str())
+        .with_status(503)
+        .expect(2)
+        .create();
+    let success = mockito::mock("GET", path.as_str())
+        .with_status(200)
+        .with_body(
+            json!({"id": "RETRY-500", "name": "Retried", "closed": false, "url": ""}).to_string(),
+        )
+        .create();
+
+    let client = client_with_retry(fast_retry(3));
+    let board = Board::get(&client, "RETRY-500")?;
+
+    assert_eq!(board.name, "Retried");
+    failure.assert();
+    success.assert();
+    Ok(())
+}
+
+#[test]
+fn test_retry_gives_up_after_max_attempts() {
+    let path = board_path("RETRY-GIVE-UP");
+    let m = mockito::mock("GET", path.as_str())
+        .with_status(500)
+        .expect(2)
+        .create();
+
+    let client = client_with_retry(fast_retry(2));
+    let error = Board::get(&client, "RETRY-GIVE-UP").expect_err("Expected an error");
+
+    assert!(matches!(error, TrelloError::Api { status: 500, .. }));
+    m.assert();
+}
+
+#[test]
+fn test_no_retry_for_non_idempotent_server_error() {
+    let m = mockito::mock("POST", "/1/boards/")
+        .match_body("name=not+retried")
+        .with_status(500)
+        .expect(1)
+        .create();
+
+    let client = client_with_retry(fast_retry(3));
+    let error = Board::create(&client, "not retried").expect_err("Expected an error");
+
+    assert!(matches!(error, TrelloError::Api { status: 500, .. }));
+    m.assert();
+}
+
+#[test]
+fn test_retry_rate_limited_non_idempotent() -> Result<()> {
+    let path = "/1/boards/";
+    let failure = mockito::mock("POST", path)
+        .match_body("name=rate+limited")
+        .with_status(429)
+        .expect(1)
+        .create();
+    let _success = mockito::mock("POST", path)
+        .match_body("name=rate+limited")
+        .with_status(200)
+        .with_body(
+            json!({"id": "RETRY-429", "name": "rate limited", "closed": false, "url": ""})
+                .to_string(),
+        )
+        .create();
+
+    let client = client_with_retry(fast_retry(2));
+    let board = Board::create(&client, "rate limited")?;
+
+    assert_eq!(board.id, "RETRY-429");
+    failure.assert();
+    Ok(())
+}
+
+#[test]
+fn test_retry_honours_retry_after() -> Result<()> {
+    let path = board_path("RETRY-AFTER");
+    let _failure = mockito::mock("GET", path.as_str())
+        .with_status(429)
+        .with_header("retry-after", "1")
+        .expect(1)
+        .create();
+    let _success = mockito::mock("GET", path.as_str())
+        .with_status(200)
+        .with_body(
+            json!({"id": "RETRY-AFTER", "name": "Later", "closed": false, "url": ""}).to_string(),
+        )
+        .create();
+
+    let client = client_with_retry(RetryPolicy {
+        max_delay_ms: 2000,
+        honour_retry_after: true,
+        ..fast_retry(2)
+    });
+    let start = Instant::now();
+    Board::get(&client, "RETRY-AFTER")?;
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    Ok(())
+}
+
+#[test]
+fn test_retry_after_exceeding_max_delay() {
+    let path = board_path("RETRY-AFTER-TOO-LONG");
+    let m = mockito::mock("GET", path.as_str())
+        .with_status(429)
+        .with_header("retry-after", "86400")
+        .expect(1)
+        .create();
+
+    let client = client_with_retry(RetryPolicy {
+        max_delay_ms: 2000,
+        honour_retry_after: true,
+        ..fast_retry(3)
+    });
+    let start = Instant::now();
+    let error = Board::get(&client, "RETRY-AFTER-TOO-LONG").expect_err("Expected an error");
+
+    assert!(matches!(
+        error,
+        TrelloError::RateLimited {
+            retry_after: Some(86400)
+        }
+    ));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    m.assert();
+}
+
+#[test]
+fn test_throttle() -> Result<()> {
+    let path = board_path("THROTTLE");
+    let m = mockito::mock("GET", path.as_str())
+        .with_status(200)
+        .with_body(
+            json!({"id": "THROTTLE", "name": "Slow", "closed": false, "url": ""}).to_string(),
+        )
+        .expect(3)
+        .create();
+
+    let mut config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    config.throttle_ms = Some(100);
+    let client = TrelloClient::new(config);
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        Board::get(&client, "THROTTLE")?;
+    }
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    m.assert();
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_credentials_from_commands() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut config = ClientConfig::new(&ClientConfig::default_host(), "", "");
+    config.key_command = Some(String::from("echo command-key"));
+    config.token_command = Some(String::from("printf 'command-token\\nignored'"));
+
+    config.resolve_credentials()?;
+    assert_eq!(config.key, "command-key");
+    assert_eq!(config.token, "command-token");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_credentials_failing_command() {
+    let mut config = ClientConfig::new(&ClientConfig::default_host(), "some-token", "");
+    config.key_command = Some(String::from("exit 1"));
+
+    let error = config.resolve_credentials().expect_err("Expected an error");
+    assert!(
+        error
+            .to_string()
+            .starts_with("Unable to read key from 'exit 1'")
+    );
+}
+
+#[test]
+fn test_resolve_credentials_missing_token() {
+    let mut config = ClientConfig::new(&ClientConfig::default_host(), "", "some-key");
+
+    let error = config.resolve_credentials().expect_err("Expected an error");
+    assert_eq!(
+        error.to_string(),
+        "No token configured. Please run tro setup"
+    );
+}
@@ -0,0 +1,153 @@
+use super::*;
+use chrono::{TimeZone, Utc};
+
+fn member() -> Member {
+    Member::new("MEMBER-1", "Shovel Knight", "shovel", "SK")
+}
+
+#[test]
+fn test_render_without_headers() {
+    let comment = Comment::new(
+        "1",
+        "Looks good to me",
+        Utc.with_ymd_and_hms(2020, 6, 28, 6, 6, 27).unwrap(),
+        member(),
+    );
+
+    assert_eq!(comment.render(false), "Looks good to me");
+}
+
+#[test]
+fn test_get_all() -> Result<()> {
+    let _m = mockito::mock(
+        "GET",
+        "/1/cards/CARD-1/actions?filter=commentCard&fields=id%2Cdate%2Cdata%2CidMemberCreator&memberCreator_fields=id%2CfullName%2Cusername%2Cinitials&limit=1000",
+    )
+    .match_header(
+        "authorization",
+        "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+    )
+    .with_status(200)
+    .with_body(
+        json!([{
+            "id": "ACTION-1",
+            "date": "2020-06-28T06:06:27.000Z",
+            "data": {"text": "Looks good to me", "card": {"id": "CARD-1"}},
+            "idMemberCreator": "MEMBER-1",
+            "memberCreator": {
+                "id": "MEMBER-1",
+                "fullName": "Shovel Knight",
+                "username": "shovel",
+                "initials": "SK",
+            },
+        }])
+        .to_string(),
+    )
+    .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Comment::get_all(&client, "CARD-1")?;
+    let expected = vec![Comment::new(
+        "ACTION-1",
+        "Looks good to me",
+        Utc.with_ymd_and_hms(2020, 6, 28, 6, 6, 27).unwrap(),
+        member(),
+    )];
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+/// Action as returned by Trello for a comment with the given id
+fn comment_action(id: usize) -> serde_json::Value {
+    json!({
+        "id": format!("ACTION-{}", id),
+        "date": "2020-06-28T06:06:27.000Z",
+        "data": {"text": format!("Comment {}", id)},
+        "idMemberCreator": "MEMBER-1",
+        "memberCreator": {"id": "MEMBER-1", "fullName": "Shovel Knight", "username": "shovel"},
+    })
+}
+
+#[test]
+fn test_get_all_pages() -> Result<()> {
+    let first_page = mockito::mock("GET", "/1/cards/CARD-1/actions")
+        .match_query(mockito::Matcher::Regex(String::from(
+            "^filter=commentCard&.*&limit=1000$",
+        )))
+        .with_status(200)
+        .with_body(json!((0..1000).map(comment_action).collect::<Vec<_>>()).to_string())
+        .expect(1)
+        .create();
+    let second_page = mockito::mock("GET", "/1/cards/CARD-1/actions")
+        .match_query(mockito::Matcher::Regex(String::from(
+            "&limit=1000&before=ACTION-999$",
+        )))
+        .with_status(200)
+        .with_body(json!([comment_action(1000)]).to_string())
+        .expect(1)
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Comment::get_all(&client, "CARD-1")?;
+    assert_eq!(result.len(), 1001);
+    assert_eq!(result[0].data.text, "Comment 0");
+    assert_eq!(result[1000].data.text, "Comment 1000");
+
+    first_page.assert();
+    second_page.assert();
+    Ok(())
+}
+
+#[test]
+fn test_create() -> Result<()> {
+    let _m = mockito::mock("POST", "/1/cards/CARD-2/actions/comments")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("text=Ship+it")
+        .with_status(200)
+        .with_body(
+            json!({
+                "id": "ACTION-2",
+                "date": "2020-06-28T06:06:27.000Z",
+                "data": {"text": "Ship it"},
+                "memberCreator": {"id": "MEMBER-1", "fullName": "Shovel Knight", "username": "shovel"},
+            })
+            .to_string(),
+        )
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Comment::create(&client, "CARD-2", "Ship it")?;
+
+    assert_eq!(result.id, "ACTION-2");
+    assert_eq!(result.data.text, "Ship it");
+    Ok(())
+}
+
+#[test]
+fn test_delete() -> Result<()> {
+    let m = mockito::mock("DELETE", "/1/cards/CARD-3/actions/ACTION-3/comments")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .with_status(200)
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    Comment::delete(&client, "CARD-3", "ACTION-3")?;
+
+    m.assert();
+    Ok(())
+}
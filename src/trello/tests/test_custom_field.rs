@@ -0,0 +1,154 @@
+use super::*;
+
+use mockito::Matcher;
+
+fn option(id: &str, text: &str) -> CustomFieldOption {
+    CustomFieldOption {
+        id: String::from(id),
+        value: CustomFieldItemValue {
+            text: Some(String::from(text)),
+            ..Default::default()
+        },
+    }
+}
+
+fn item(
+    field_id: &str,
+    value: Option<CustomFieldItemValue>,
+    id_value: Option<&str>,
+) -> CustomFieldItem {
+    CustomFieldItem {
+        id: String::from("item"),
+        id_custom_field: String::from(field_id),
+        value,
+        id_value: id_value.map(String::from),
+    }
+}
+
+#[test]
+fn test_format_value_list() {
+    let field = CustomField::new(
+        "f1",
+        "Priority",
+        CustomFieldType::List,
+        vec![option("o1", "High"), option("o2", "Low")],
+    );
+
+    let items = vec![item("f1", None, Some("o2"))];
+
+    assert_eq!(field.format_value(&items), Some(String::from("Low")));
+}
+
+#[test]
+fn test_format_value_checkbox() {
+    let field = CustomField::new("f1", "Blocked", CustomFieldType::Checkbox, vec![]);
+
+    let items = vec![item(
+        "f1",
+        Some(CustomFieldItemValue {
+            checked: Some(String::from("true")),
+            ..Default::default()
+        }),
+        None,
+    )];
+
+    assert_eq!(field.format_value(&items), Some(String::from("yes")));
+}
+
+#[test]
+fn test_format_value_missing() {
+    let field = CustomField::new("f1", "Blocked", CustomFieldType::Checkbox, vec![]);
+
+    assert_eq!(field.format_value(&[item("f2", None, None)]), None);
+}
+
+#[test]
+fn test_get_all() -> Result<()> {
+    let _m = mockito::mock("GET", "/1/boards/BOARD-1/customFields")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .with_status(200)
+        .with_body(
+            json!([
+                {"id": "f1", "name": "Points", "type": "number", "idModel": "BOARD-1"},
+                {
+                    "id": "f2",
+                    "name": "Priority",
+                    "type": "list",
+                    "options": [
+                        {"id": "o1", "idCustomField": "f2", "value": {"text": "High"}, "color": "red"},
+                    ],
+                },
+            ])
+            .to_string(),
+        )
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = CustomField::get_all(&client, "BOARD-1")?;
+    let expected = vec![
+        CustomField::new("f1", "Points", CustomFieldType::Number, vec![]),
+        CustomField::new(
+            "f2",
+            "Priority",
+            CustomFieldType::List,
+            vec![option("o1", "High")],
+        ),
+    ];
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_update_number() -> Result<()> {
+    let m = mockito::mock("PUT", "/1/cards/CARD-1/customField/f1/item")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body(Matcher::Json(json!({"value": {"number": "3.5"}})))
+        .with_status(200)
+        .with_body(json!({}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let field = CustomField::new("f1", "Points", CustomFieldType::Number, vec![]);
+    CustomFieldItem::update(
+        &client,
+        "CARD-1",
+        &field,
+        Some(&CustomFieldValue::Number(3.5)),
+    )?;
+
+    m.assert();
+    Ok(())
+}
+
+#[test]
+fn test_update_clear_list() -> Result<()> {
+    let m = mockito::mock("PUT", "/1/cards/CARD-1/customField/f2/item")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body(Matcher::Json(json!({"idValue": ""})))
+        .with_status(200)
+        .with_body(json!({}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let field = CustomField::new("f2", "Priority", CustomFieldType::List, vec![]);
+    CustomFieldItem::update(&client, "CARD-1", &field, None)?;
+
+    m.assert();
+    Ok(())
+}
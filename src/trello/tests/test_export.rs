@@ -0,0 +1,160 @@
+use super::*;
+use chrono::{TimeZone, Utc};
+
+/// Board contents reduced to what an import is expected to preserve
+fn summarise(client: &TrelloClient, board_id: &str) -> Result<Vec<(String, Vec<String>)>> {
+    let lists = List::get_all(client, board_id, true)?;
+
+    Ok(lists
+        .into_iter()
+        .map(|l| {
+            let cards = l
+                .cards
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| {
+                    let labels = c
+                        .labels
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|l| format!("{}:{:?}", l.name, l.color))
+                        .collect::<Vec<String>>();
+                    format!("{} ({}) {:?}", c.name, c.desc, labels)
+                })
+                .collect();
+            (l.name, cards)
+        })
+        .collect())
+}
+
+#[test]
+fn test_export_and_import() -> Result<()> {
+    let client = FakeTrello::new().client();
+
+    let board = Board::create_with_defaults(&client, "Original", false, false)?;
+    let urgent = Label::create(&client, &board.id, "Urgent", Some(&LabelColor::Red))?;
+    Label::create(&client, &board.id, "", Some(&LabelColor::SkyLight))?;
+    Label::create(&client, &board.id, "Someday", None)?;
+    let backlog = List::create(&client, &board.id, "Backlog")?;
+    let doing = List::create(&client, &board.id, "Doing")?;
+
+    let first = Card::create(
+        &client,
+        &backlog.id,
+        &Card::new("", "First", "one", None, "", None),
+    )?;
+    Card::create(
+        &client,
+        &backlog.id,
+        &Card::new("", "Second", "two", None, "", None),
+    )?;
+    Card::create(
+        &client,
+        &doing.id,
+        &Card::new("", "Third", "", None, "", None),
+    )?;
+    Label::apply(&client, &first.id, &urgent.id)?;
+    Attachment::link(&client, &first.id, "https://example.com/spec", "Spec")?;
+
+    let export = BoardExport::create(&client, &board)?;
+    assert_eq!(export.version, EXPORT_VERSION);
+    assert_eq!(export.lists.len(), 2);
+    assert_eq!(export.lists[0].cards[0].attachments[0].name, "Spec");
+
+    let contents = serde_json::to_string(&export).unwrap();
+    let parsed: BoardExport = serde_json::from_str(&contents).unwrap();
+    assert_eq!(parsed, export);
+
+    let imported = parsed.import(&client, "Restored")?;
+    assert_eq!(imported.name, "Restored");
+    assert_ne!(imported.id, board.id);
+
+    assert_eq!(
+        summarise(&client, &imported.id)?,
+        summarise(&client, &board.id)?
+    );
+
+    let colors = Label::get_all(&client, &imported.id)?
+        .into_iter()
+        .map(|l| l.color)
+        .collect::<Vec<Option<LabelColor>>>();
+    assert_eq!(
+        colors,
+        vec![Some(LabelColor::Red), Some(LabelColor::SkyLight), None]
+    );
+
+    let imported_lists = List::get_all(&client, &imported.id, true)?;
+    let imported_first = &imported_lists[0].cards.as_ref().unwrap()[0];
+    let attachments = Attachment::get_all(&client, &imported_first.id)?;
+    assert_eq!(attachments[0].name, "Spec");
+    assert_eq!(attachments[0].url, "https://example.com/spec");
+    Ok(())
+}
+
+#[test]
+fn test_export_format() {
+    let mut card = Card::new("CARD-1", "Card", "", None, "", None);
+    card.checklists = Some(vec![Checklist::new(
+        "CHECKLIST-1",
+        "Todo",
+        vec![CheckItem::new("ITEM-1", "Item", CheckItemState::Complete)],
+    )]);
+
+    let export = BoardExport {
+        version: EXPORT_VERSION,
+        exported_at: Utc.with_ymd_and_hms(2020, 7, 14, 10, 0, 0).unwrap(),
+        board: Board::new("BOARD-1", "Board", None, ""),
+        labels: vec![],
+        lists: vec![ListExport {
+            list: List::new("LIST-1", "List", None),
+            cards: vec![CardExport {
+                card,
+                comments: vec![Comment {
+                    id: String::from("COMMENT-1"),
+                    date: Utc.with_ymd_and_hms(2020, 7, 14, 9, 0, 0).unwrap(),
+                    data: CommentData {
+                        text: String::from("Hello"),
+                    },
+                    member_creator: Member::new("MEMBER-1", "Some User", "someuser", "SU"),
+                }],
+                attachments: vec![],
+            }],
+        }],
+    };
+
+    let value = serde_json::to_value(&export).unwrap();
+
+    assert_eq!(value["exportedAt"], "2020-07-14T10:00:00Z");
+    assert_eq!(value["lists"][0]["id"], "LIST-1");
+    assert_eq!(value["lists"][0]["cards"][0]["id"], "CARD-1");
+    assert_eq!(
+        value["lists"][0]["cards"][0]["checklists"][0]["checkItems"][0]["state"],
+        "complete"
+    );
+    assert_eq!(
+        value["lists"][0]["cards"][0]["comments"][0]["data"]["text"],
+        "Hello"
+    );
+
+    let parsed: BoardExport = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed, export);
+}
+
+#[test]
+fn test_import_unsupported_version() {
+    let client = FakeTrello::new().client();
+    let export = BoardExport {
+        version: EXPORT_VERSION + 1,
+        exported_at: Utc::now(),
+        board: Board::new("BOARD-1", "Board", None, ""),
+        labels: vec![],
+        lists: vec![],
+    };
+
+    let error = export
+        .import(&client, "Board")
+        .expect_err("Expected an error");
+
+    assert!(matches!(error, TrelloError::Export(_)));
+    assert!(Board::get_all(&client).unwrap().is_empty());
+}
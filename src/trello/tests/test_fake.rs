@@ -0,0 +1,160 @@
+use super::*;
+
+#[test]
+fn test_board_create_with_default_lists_and_labels() -> Result<()> {
+    let client = FakeTrello::new().client();
+
+    let board = Board::create(&client, "My Board")?;
+    assert_eq!(board.name, "My Board");
+    assert_eq!(board.id.len(), 24);
+    assert!(board.url.ends_with("/my-board"));
+
+    let lists = List::get_all(&client, &board.id, true)?;
+    let names = lists.iter().map(|l| l.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["To Do", "Doing", "Done"]);
+    assert_eq!(lists[0].cards, Some(vec![]));
+
+    let labels = Label::get_all(&client, &board.id)?;
+    assert_eq!(labels.len(), 6);
+    assert_eq!(labels[0].color, Some(LabelColor::Green));
+
+    assert_eq!(Board::get_all(&client)?, vec![board]);
+    Ok(())
+}
+
+#[test]
+fn test_card_lifecycle() -> Result<()> {
+    let client = FakeTrello::new().client();
+
+    let board = Board::create(&client, "Board")?;
+    let lists = List::get_all(&client, &board.id, false)?;
+    let (todo, done) = (&lists[0], &lists[2]);
+
+    let first = Card::create(
+        &client,
+        &todo.id,
+        &Card::new("", "First", "", None, "", None),
+    )?;
+    let second = Card::create(
+        &client,
+        &todo.id,
+        &Card::new("", "Second", "desc", None, "", None),
+    )?;
+    assert_ne!(first.id, second.id);
+    assert_eq!(second.id_board, board.id);
+
+    let cards = Card::get_all(&client, &todo.id)?;
+    let names = cards.iter().map(|c| c.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["First", "Second"]);
+
+    Card::change_list(&client, &first.id, &done.id)?;
+    let label = &Label::get_all(&client, &board.id)?[0];
+    Label::apply(&client, &first.id, &label.id)?;
+
+    let card = Card::get(&client, &first.id)?;
+    assert_eq!(card.labels, Some(vec![label.clone()]));
+    assert_eq!(Card::get_all(&client, &done.id)?.len(), 1);
+
+    let mut closed = card.clone();
+    closed.closed = true;
+    Card::update(&client, &closed)?;
+    assert!(Card::get_all(&client, &done.id)?.is_empty());
+    assert!(Card::get(&client, &first.id)?.closed);
+    Ok(())
+}
+
+#[test]
+fn test_clients_share_state() -> Result<()> {
+    let fake = FakeTrello::new();
+
+    let board = Board::create(&fake.client(), "Shared")?;
+
+    assert_eq!(Board::get(&fake.client(), &board.id)?, board);
+    Ok(())
+}
+
+#[test]
+fn test_attachments() -> Result<()> {
+    let client = FakeTrello::new().client();
+    let board = Board::create(&client, "Board")?;
+    let list = &List::get_all(&client, &board.id, false)?[0];
+    let card = Card::create(
+        &client,
+        &list.id,
+        &Card::new("", "Card", "", None, "", None),
+    )?;
+
+    let mut file = NamedTempFile::new()?;
+    write!(file, "hello world")?;
+    let path = file.path().to_str().unwrap();
+
+    let attachment = Attachment::apply(&client, &card.id, path)?;
+    let filename = file.path().file_name().unwrap().to_str().unwrap();
+
+    assert_eq!(attachment.name, filename);
+    assert_eq!(
+        Attachment::get_all(&client, &card.id)?,
+        vec![attachment.clone()]
+    );
+
+    let mut contents = vec![];
+    Attachment::download(&client, &card.id, &attachment, &mut contents)?;
+    assert_eq!(contents, b"hello world");
+
+    let upload = Attachment::upload(&client, &card.id, "notes.txt", "some notes".as_bytes())?;
+    assert_eq!(upload.name, "notes.txt");
+    assert_eq!(upload.bytes, Some(10));
+    assert!(upload.is_upload);
+
+    let link = Attachment::link(&client, &card.id, "https://example.com", "Example")?;
+    assert!(!link.is_upload);
+
+    Attachment::delete(&client, &card.id, &attachment.id)?;
+    assert_eq!(Attachment::get_all(&client, &card.id)?, vec![upload, link]);
+    Ok(())
+}
+
+#[test]
+fn test_search() -> Result<()> {
+    let client = FakeTrello::new().client();
+    let board = Board::create(&client, "Groceries")?;
+    let list = &List::get_all(&client, &board.id, false)?[0];
+    Card::create(
+        &client,
+        &list.id,
+        &Card::new("", "Buy milk", "", None, "", None),
+    )?;
+    Card::create(
+        &client,
+        &list.id,
+        &Card::new("", "Buy eggs", "", None, "", None),
+    )?;
+
+    let result = search(&client, "milk", &SearchOptions::default())?;
+
+    assert_eq!(result.cards.len(), 1);
+    assert_eq!(result.cards[0].name, "Buy milk");
+    assert!(result.boards.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_unknown_object() {
+    let client = FakeTrello::new().client();
+
+    let error = Board::get(&client, "5f0d6c80ffffffffffffffff").expect_err("Expected an error");
+
+    assert!(matches!(error, TrelloError::NotFound(_)));
+}
+
+#[test]
+fn test_invalid_value() {
+    let client = FakeTrello::new().client();
+
+    let error = Board::create(&client, "").expect_err("Expected an error");
+
+    assert!(matches!(
+        error,
+        TrelloError::Api { status: 400, message } if message == "invalid value for name"
+    ));
+}
@@ -67,3 +67,132 @@ fn test_remove() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_create() -> Result<()> {
+    let _m = mockito::mock("POST", "/1/labels/")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("name=Urgent+Bills&color=red&idBoard=BOARD-ID")
+        .with_status(200)
+        .with_body(json!({"id": "NEW-LABEL", "name": "Urgent Bills", "color": "red"}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Label::create(&client, "BOARD-ID", "Urgent Bills", Some(&LabelColor::Red))?;
+
+    assert_eq!(result, Label::new("NEW-LABEL", "Urgent Bills", "red"));
+    Ok(())
+}
+
+#[test]
+fn test_update() -> Result<()> {
+    let _m = mockito::mock("PUT", "/1/labels/LABEL-ID")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .match_body("name=Paid&color=green")
+        .with_status(200)
+        .with_body(json!({"id": "LABEL-ID", "name": "Paid", "color": "green"}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let label = Label::new("LABEL-ID", "Paid", "green");
+    let result = Label::update(&client, &label)?;
+
+    assert_eq!(result, label);
+    Ok(())
+}
+
+#[test]
+fn test_delete() -> Result<()> {
+    let _m = mockito::mock("DELETE", "/1/labels/OLD-LABEL")
+        .match_header(
+            "authorization",
+            "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+        )
+        .with_status(200)
+        .with_body(json!({}).to_string())
+        .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    Label::delete(&client, "OLD-LABEL")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_deserialize_colors() {
+    let labels: Vec<Label> = serde_json::from_value(json!([
+        {"id": "1", "name": "Bold", "color": "green_dark"},
+        {"id": "2", "name": "Colourless", "color": null},
+        {"id": "3", "name": "Absent"},
+        {"id": "4", "name": "Future", "color": "turquoise"},
+    ]))
+    .unwrap();
+
+    assert_eq!(labels[0].color, Some(LabelColor::GreenDark));
+    assert_eq!(labels[1].color, None);
+    assert_eq!(labels[2].color, None);
+    assert_eq!(
+        labels[3].color,
+        Some(LabelColor::Other(String::from("turquoise")))
+    );
+
+    let value = serde_json::to_value(&labels).unwrap();
+    assert_eq!(value[0]["color"], "green_dark");
+    assert_eq!(value[1]["color"], json!(null));
+    assert_eq!(value[3]["color"], "turquoise");
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(
+        "sky_light".parse::<LabelColor>().unwrap(),
+        LabelColor::SkyLight
+    );
+    assert_eq!("black".parse::<LabelColor>().unwrap(), LabelColor::Black);
+
+    let error = "turquoise"
+        .parse::<LabelColor>()
+        .expect_err("Expected error");
+    assert!(matches!(error, TrelloError::LabelColor(_)));
+
+    for color in LabelColor::PALETTE {
+        assert_eq!(&color.as_str().parse::<LabelColor>().unwrap(), color);
+    }
+    assert_eq!(LabelColor::PALETTE.len(), 30);
+}
+
+#[test]
+fn test_simple_render() {
+    let label = Label::new("", "Subtle", "sky_light");
+    assert_eq!(
+        label.simple_render(),
+        "\u{1b}[48;2;198;237;251;30m Subtle \u{1b}[0m"
+    );
+
+    let label = Label::new("", "Colourless", "");
+    assert_eq!(
+        label.simple_render(),
+        "\u{1b}[48;2;138;147;163;37m Colourless \u{1b}[0m"
+    );
+}
+
+#[test]
+fn test_simple_render_unnamed() {
+    let label = Label::new("", "", "red_dark");
+    assert_eq!(
+        label.simple_render(),
+        "\u{1b}[48;2;201;55;44;37m    \u{1b}[0m"
+    );
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn test_get_all_for_board() -> Result<()> {
+    let _m = mockito::mock(
+        "GET",
+        "/1/boards/BOARD-1/members?fields=id%2CfullName%2Cusername%2Cinitials",
+    )
+    .match_header(
+        "authorization",
+        "OAuth oauth_consumer_key=\"some-key\", oauth_token=\"some-token\"",
+    )
+    .with_status(200)
+    .with_body(
+        json!([
+            {"id": "m1", "fullName": "King Knight", "username": "king", "initials": "KK"},
+            {"id": "m2", "fullName": "Shovel Knight", "username": "shovel", "initials": "SK"},
+        ])
+        .to_string(),
+    )
+    .create();
+
+    let config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    let client = TrelloClient::new(config);
+
+    let result = Member::get_all_for_board(&client, "BOARD-1")?;
+    let expected = vec![
+        Member::new("m1", "King Knight", "king", "KK"),
+        Member::new("m2", "Shovel Knight", "shovel", "SK"),
+    ];
+
+    assert_eq!(result, expected);
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use super::*;
+
+/// Abridged board in the format produced by Trello's "Export as JSON"
+fn export() -> String {
+    json!({
+        "id": "BOARD-1",
+        "name": "Audit",
+        "desc": "",
+        "closed": false,
+        "url": "https://trello.com/b/abcd1234/audit",
+        "prefs": {"background": "blue"},
+        "labelNames": {"green": "Done", "red": ""},
+        "labels": [
+            {"id": "LABEL-1", "idBoard": "BOARD-1", "name": "Done", "color": "green"},
+            {"id": "LABEL-2", "idBoard": "BOARD-1", "name": "Unsorted", "color": null},
+        ],
+        "lists": [
+            {"id": "LIST-2", "name": "Reviewed", "closed": false, "idBoard": "BOARD-1", "pos": 32768},
+            {"id": "LIST-1", "name": "Pending", "closed": false, "idBoard": "BOARD-1", "pos": 16384},
+            {"id": "LIST-3", "name": "Old", "closed": true, "idBoard": "BOARD-1", "pos": 49152},
+        ],
+        "cards": [
+            {
+                "id": "CARD-2",
+                "name": "Expense report",
+                "desc": "Quarterly expenses",
+                "closed": false,
+                "idList": "LIST-1",
+                "idBoard": "BOARD-1",
+                "idLabels": ["LABEL-1", "LABEL-2"],
+                "idMembers": ["MEMBER-1"],
+                "pos": 32768,
+                "due": "2020-07-14T10:00:00.000Z",
+                "dueComplete": true,
+                "url": "https://trello.com/c/efgh5678/2-expense-report",
+                "attachments": [
+                    {"id": "ATTACHMENT-1", "name": "receipts.pdf", "url": "https://trello.com/receipts.pdf", "bytes": 1024},
+                ],
+                "labels": [],
+            },
+            {
+                "id": "CARD-1",
+                "name": "Invoices",
+                "desc": "",
+                "closed": false,
+                "idList": "LIST-1",
+                "idBoard": "BOARD-1",
+                "idLabels": [],
+                "pos": 16384,
+                "due": null,
+                "url": "https://trello.com/c/ijkl9012/1-invoices",
+            },
+            {
+                "id": "CARD-3",
+                "name": "Archived invoices",
+                "desc": "",
+                "closed": true,
+                "idList": "LIST-1",
+                "idBoard": "BOARD-1",
+                "idLabels": [],
+                "pos": 49152,
+                "due": null,
+                "url": "https://trello.com/c/mnop3456/3-archived-invoices",
+            },
+        ],
+        "checklists": [
+            {
+                "id": "CHECKLIST-1",
+                "name": "Steps",
+                "idCard": "CARD-2",
+                "pos": 16384,
+                "checkItems": [
+                    {"id": "ITEM-2", "name": "Sign", "state": "incomplete", "pos": 32768},
+                    {"id": "ITEM-1", "name": "Collect", "state": "complete", "pos": 16384},
+                ],
+            },
+        ],
+        "actions": [
+            {
+                "id": "ACTION-2",
+                "type": "commentCard",
+                "date": "2020-07-15T09:00:00.000Z",
+                "data": {"text": "Looks good", "card": {"id": "CARD-2", "name": "Expense report"}},
+                "memberCreator": {"id": "MEMBER-1", "fullName": "Some Auditor", "username": "auditor"},
+            },
+            {
+                "id": "ACTION-1",
+                "type": "createCard",
+                "date": "2020-07-14T09:00:00.000Z",
+                "data": {"card": {"id": "CARD-2", "name": "Expense report"}},
+                "memberCreator": {"id": "MEMBER-1", "fullName": "Some Auditor", "username": "auditor"},
+            },
+        ],
+        "members": [
+            {"id": "MEMBER-1", "fullName": "Some Auditor", "username": "auditor", "initials": "SA"},
+        ],
+    })
+    .to_string()
+}
+
+#[test]
+fn test_load() -> Result<()> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(export().as_bytes())?;
+
+    let client = OfflineTrello::load(file.path())?.client();
+    let boards = Board::get_all(&client)?;
+
+    assert_eq!(
+        boards,
+        vec![Board::new(
+            "BOARD-1",
+            "Audit",
+            None,
+            "https://trello.com/b/abcd1234/audit"
+        )]
+    );
+    assert!(client.is_read_only());
+    Ok(())
+}
+
+#[test]
+fn test_load_invalid() {
+    let error = OfflineTrello::from_json("{\"name\": \"Missing id\"}").expect_err("Expected error");
+
+    assert!(matches!(error, TrelloError::Offline(_)));
+}
+
+#[test]
+fn test_retrieve_nested() -> Result<()> {
+    let client = OfflineTrello::from_json(&export())?.client();
+
+    let mut board = Board::get(&client, "BOARD-1")?;
+    board.retrieve_nested(&client)?;
+
+    let lists = board.lists.unwrap();
+    assert_eq!(
+        lists.iter().map(|l| l.name.as_str()).collect::<Vec<&str>>(),
+        vec!["Pending", "Reviewed"]
+    );
+
+    let cards = lists[0].cards.as_ref().unwrap();
+    assert_eq!(
+        cards.iter().map(|c| c.name.as_str()).collect::<Vec<&str>>(),
+        vec!["Invoices", "Expense report"]
+    );
+
+    let card = &cards[1];
+    assert_eq!(
+        card.labels,
+        Some(vec![
+            Label::new("LABEL-1", "Done", "green"),
+            Label::new("LABEL-2", "Unsorted", ""),
+        ])
+    );
+    assert_eq!(card.id_board, "BOARD-1");
+    assert!(card.due_complete);
+    assert_eq!(
+        card.members,
+        Some(vec![Member::new(
+            "MEMBER-1",
+            "Some Auditor",
+            "auditor",
+            "SA"
+        )])
+    );
+    assert!(lists[1].cards.as_ref().unwrap().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_card_contents() -> Result<()> {
+    let client = OfflineTrello::from_json(&export())?.client();
+
+    let checklists = Checklist::get_all(&client, "CARD-2")?;
+    assert_eq!(
+        checklists,
+        vec![Checklist::new(
+            "CHECKLIST-1",
+            "Steps",
+            vec![
+                CheckItem::new("ITEM-1", "Collect", CheckItemState::Complete),
+                CheckItem::new("ITEM-2", "Sign", CheckItemState::Incomplete),
+            ]
+        )]
+    );
+
+    let comments = Comment::get_all(&client, "CARD-2")?;
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].data.text, "Looks good");
+    assert_eq!(comments[0].member_creator.username, "auditor");
+
+    let attachments = Attachment::get_all(&client, "CARD-2")?;
+    assert_eq!(attachments[0].name, "receipts.pdf");
+
+    assert!(Checklist::get_all(&client, "CARD-1")?.is_empty());
+    assert!(CustomField::get_all(&client, "BOARD-1")?.is_empty());
+
+    let error = Card::get(&client, "CARD-404").expect_err("Expected error");
+    assert!(matches!(error, TrelloError::NotFound(_)));
+    Ok(())
+}
+
+#[test]
+fn test_search() -> Result<()> {
+    let client = OfflineTrello::from_json(&export())?.client();
+    let names = |query: &str, partial: bool| -> Result<Vec<String>> {
+        let options = SearchOptions {
+            partial,
+            ..Default::default()
+        };
+        Ok(search(&client, query, &options)?
+            .cards
+            .into_iter()
+            .map(|c| c.name)
+            .collect())
+    };
+
+    assert_eq!(
+        names("invoices", false)?,
+        vec!["Invoices", "Archived invoices"]
+    );
+    assert_eq!(names("invoices is:open", false)?, vec!["Invoices"]);
+    assert_eq!(names("invoice", false)?, Vec::<String>::new());
+    assert_eq!(
+        names("invoice", true)?,
+        vec!["Invoices", "Archived invoices"]
+    );
+    assert_eq!(
+        names("quarterly label:done", false)?,
+        vec!["Expense report"]
+    );
+    assert_eq!(names("label:green", false)?, vec!["Expense report"]);
+
+    let error = names("has:description", false).expect_err("Expected error");
+    assert!(matches!(error, TrelloError::Offline(_)));
+    Ok(())
+}
+
+#[test]
+fn test_read_only() -> Result<()> {
+    let client = OfflineTrello::from_json(&export())?.client();
+
+    let error = List::create(&client, "BOARD-1", "New").expect_err("Expected error");
+    assert_eq!(
+        error.to_string(),
+        "Offline export: Cannot POST /1/lists as the export is read-only"
+    );
+
+    let error = Card::set_due_complete(&client, "CARD-1", true).expect_err("Expected error");
+    assert!(matches!(error, TrelloError::Offline(_)));
+
+    let error = Member::me(&client).expect_err("Expected error");
+    assert!(matches!(error, TrelloError::Offline(_)));
+    Ok(())
+}
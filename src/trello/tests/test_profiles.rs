@@ -0,0 +1,205 @@
+use super::*;
+use tempfile::TempDir;
+
+#[test]
+fn test_from_toml_single_config() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut profiles = Profiles::from_toml(
+        r#"
+        key = "some-key"
+        token = "some-token"
+        "#,
+    )?;
+
+    assert_eq!(profiles.default_profile, None);
+    assert_eq!(
+        profiles.profiles.keys().collect::<Vec<_>>(),
+        vec!["default"]
+    );
+
+    let config = profiles.take(Some(DEFAULT_PROFILE))?;
+    assert_eq!(config.key, "some-key");
+    assert_eq!(config.token, "some-token");
+    assert_eq!(config.host, ClientConfig::default_host());
+    assert_eq!(config.profile.as_deref(), Some("default"));
+    Ok(())
+}
+
+#[test]
+fn test_from_toml_profiles() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let mut profiles = Profiles::from_toml(
+        r#"
+        default_profile = "work"
+
+        [profiles.personal]
+        key = "personal-key"
+        token = "personal-token"
+
+        [profiles.work]
+        key = "work-key"
+        token = "work-token"
+        fuzzy = true
+        "#,
+    )?;
+
+    assert_eq!(profiles.default_profile.as_deref(), Some("work"));
+    assert_eq!(profiles.resolve(Some("personal"))?, "personal");
+
+    let config = profiles.take(Some("work"))?;
+    assert_eq!(config.key, "work-key");
+    assert!(config.fuzzy);
+    assert_eq!(config.profile.as_deref(), Some("work"));
+
+    let error = profiles
+        .take(Some("missing"))
+        .expect_err("Expected an error");
+    assert_eq!(
+        error.to_string(),
+        "Profile 'missing' not found. Available profiles: personal"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_insert_first_profile_becomes_default() {
+    let mut profiles = Profiles::default();
+
+    profiles
+        .insert("work", ClientConfig::new("", "work-token", "work-key"))
+        .unwrap();
+    profiles
+        .insert("personal", ClientConfig::new("", "token", "key"))
+        .unwrap();
+
+    assert_eq!(profiles.default_profile.as_deref(), Some("work"));
+    assert_eq!(profiles.profiles.len(), 2);
+}
+
+#[test]
+fn test_set_default() {
+    let mut profiles = Profiles::default();
+    profiles
+        .insert("work", ClientConfig::new("", "work-token", "work-key"))
+        .unwrap();
+    profiles
+        .insert("personal", ClientConfig::new("", "token", "key"))
+        .unwrap();
+
+    assert!(profiles.set_default("missing").is_err());
+    assert_eq!(profiles.default_profile.as_deref(), Some("work"));
+
+    assert!(profiles.set_default("personal").is_ok());
+    assert_eq!(profiles.default_profile.as_deref(), Some("personal"));
+}
+
+#[test]
+fn test_set_credentials_keeps_settings() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("config.toml");
+
+    let mut work = ClientConfig::new("https://trello.example.com", "", "old-key");
+    work.token_command = Some(String::from("pass trello/token"));
+    work.fuzzy = true;
+    work.throttle_ms = Some(100);
+    work.cache = Some(CacheConfig {
+        ttl_secs: 60,
+        ..CacheConfig::default()
+    });
+    let mut profiles = Profiles::default();
+    profiles.insert("work", work)?;
+
+    profiles.set_credentials("work", "new-key", "new-token")?;
+    profiles.set_credentials("personal", "key", "token")?;
+    profiles.save(&path)?;
+
+    let contents = std::fs::read_to_string(&path)?;
+    assert!(!contents.contains("new-token"));
+
+    let mut loaded = Profiles::load(&path)?;
+    let work = loaded.take(Some("work"))?;
+    assert_eq!(work.host, "https://trello.example.com");
+    assert_eq!(work.key, "new-key");
+    assert_eq!(work.token, "");
+    assert_eq!(work.token_command.as_deref(), Some("pass trello/token"));
+    assert!(work.fuzzy);
+    assert_eq!(work.throttle_ms, Some(100));
+    assert_eq!(work.cache.map(|c| c.ttl_secs), Some(60));
+
+    let personal = loaded.take(Some("personal"))?;
+    assert_eq!(personal.key, "key");
+    assert_eq!(personal.token, "token");
+    Ok(())
+}
+
+#[test]
+fn test_invalid_names_are_rejected() {
+    let mut profiles = Profiles::default();
+
+    for name in ["", "../outside", "/absolute", "work/nested", ".."] {
+        let error = profiles
+            .insert(name, ClientConfig::new("", "token", "key"))
+            .expect_err("Expected an error");
+        assert!(error.to_string().contains("Invalid profile name"));
+        assert!(profiles.resolve(Some(name)).is_err());
+    }
+    assert!(profiles.profiles.is_empty());
+
+    profiles
+        .insert("work_2-b", ClientConfig::new("", "token", "key"))
+        .unwrap();
+    assert_eq!(profiles.resolve(Some("work_2-b")).unwrap(), "work_2-b");
+}
+
+#[test]
+fn test_save_and_load() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("tro").join("config.toml");
+    assert!(Profiles::load(&path)?.profiles.is_empty());
+
+    let mut work = ClientConfig::new(&ClientConfig::default_host(), "work-token", "work-key");
+    work.fuzzy = true;
+    work.throttle_ms = Some(100);
+    work.cache = Some(CacheConfig::default());
+
+    let mut profiles = Profiles::default();
+    profiles.insert("work", work)?;
+    profiles.insert(
+        "personal",
+        ClientConfig::new(&ClientConfig::default_host(), "token", "key"),
+    )?;
+    profiles.save(&path)?;
+
+    let mut loaded = Profiles::load(&path)?;
+    assert_eq!(loaded.default_profile.as_deref(), Some("work"));
+
+    let work = loaded.take(None)?;
+    assert_eq!(work.token, "work-token");
+    assert!(work.fuzzy);
+    assert_eq!(work.throttle_ms, Some(100));
+
+    let personal = loaded.take(Some("personal"))?;
+    assert_eq!(personal.token, "token");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_save_restricts_permissions() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new()?;
+    let path = dir.path().join("config.toml");
+    let mut profiles = Profiles::default();
+    profiles.insert("work", ClientConfig::new("", "work-token", "work-key"))?;
+
+    profiles.save(&path)?;
+    assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+    assert!(!Profiles::is_world_readable(&path));
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o644))?;
+    assert!(Profiles::is_world_readable(&path));
+
+    profiles.save(&path)?;
+    assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use super::*;
+
+const TEMPLATE: &str = r#"
+name = "Website"
+
+[[labels]]
+name = "Bug"
+color = "red"
+
+[[labels]]
+color = "sky"
+
+[[lists]]
+name = "Backlog"
+
+[[lists.cards]]
+name = "Write the about page"
+desc = "Who we are"
+labels = ["Bug"]
+
+[[lists.cards]]
+name = "Add a contact form"
+
+[[lists]]
+name = "Done"
+"#;
+
+fn list_names(client: &TrelloClient, board_id: &str) -> Result<Vec<String>> {
+    Ok(List::get_all(client, board_id, false)?
+        .into_iter()
+        .map(|l| l.name)
+        .collect())
+}
+
+#[test]
+fn test_parse() -> Result<()> {
+    let template: BoardTemplate = TEMPLATE.parse()?;
+
+    assert_eq!(template.name, "Website");
+    assert_eq!(
+        template.labels,
+        vec![
+            LabelTemplate {
+                name: String::from("Bug"),
+                color: Some(LabelColor::Red),
+            },
+            LabelTemplate {
+                name: String::from(""),
+                color: Some(LabelColor::Sky),
+            },
+        ]
+    );
+    assert_eq!(template.lists[0].cards[0].desc, "Who we are");
+    assert_eq!(template.lists[0].cards[1].labels, Vec::<String>::new());
+    assert!(template.lists[1].cards.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_parse_invalid() {
+    let error = "lists = []"
+        .parse::<BoardTemplate>()
+        .expect_err("Expected error");
+    assert!(matches!(error, TrelloError::Template(_)));
+
+    let error = "name = 'Website'\n[[lists]]\nname = 'Todo'\n[[lists.cards]]\nname = 'Card'\nlabels = ['Bug']"
+        .parse::<BoardTemplate>()
+        .expect_err("Expected error");
+    assert_eq!(
+        error.to_string(),
+        "Template error: Label 'Bug' of card 'Card' is not declared in the template"
+    );
+}
+
+#[test]
+fn test_parse_invalid_color() {
+    let error = "name = 'Website'\n[[labels]]\nname = 'Bug'\ncolor = 'turquoise'"
+        .parse::<BoardTemplate>()
+        .expect_err("Expected error");
+
+    assert!(matches!(error, TrelloError::Template(_)));
+    assert!(error.to_string().contains("Unknown color 'turquoise'"));
+}
+
+#[test]
+fn test_apply() -> Result<()> {
+    let client = FakeTrello::new().client();
+    let template: BoardTemplate = TEMPLATE.parse()?;
+
+    let applied = template.apply(&client, false)?;
+
+    assert!(applied.board_created);
+    assert_eq!(applied.board.name, "Website");
+    assert_eq!(applied.labels.len(), 2);
+    assert_eq!(applied.lists.len(), 2);
+    assert_eq!(applied.cards.len(), 2);
+
+    assert_eq!(
+        list_names(&client, &applied.board.id)?,
+        vec!["Backlog", "Done"]
+    );
+
+    let lists = List::get_all(&client, &applied.board.id, true)?;
+    let cards = lists[0].cards.as_ref().unwrap();
+    assert_eq!(cards[0].name, "Write the about page");
+    assert_eq!(cards[0].desc, "Who we are");
+    assert_eq!(
+        cards[0].labels.as_ref().unwrap()[0].name,
+        String::from("Bug")
+    );
+    assert_eq!(cards[1].name, "Add a contact form");
+
+    // Trello's default labels are kept alongside the ones from the template
+    let labels = Label::get_all(&client, &applied.board.id)?;
+    assert!(
+        labels
+            .iter()
+            .any(|l| l.name == "Bug" && l.color == Some(LabelColor::Red))
+    );
+    assert!(
+        labels
+            .iter()
+            .any(|l| l.name.is_empty() && l.color == Some(LabelColor::Sky))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_default_lists() -> Result<()> {
+    let client = FakeTrello::new().client();
+    let template: BoardTemplate = "name = 'Website'\n[[lists]]\nname = 'Backlog'".parse()?;
+
+    let applied = template.apply(&client, true)?;
+
+    assert_eq!(
+        list_names(&client, &applied.board.id)?,
+        vec!["To Do", "Doing", "Done", "Backlog"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_idempotent() -> Result<()> {
+    let client = FakeTrello::new().client();
+    let template: BoardTemplate = TEMPLATE.parse()?;
+
+    let first = template.apply(&client, false)?;
+    let second = template.apply(&client, false)?;
+
+    assert!(second.is_empty());
+    assert_eq!(second.board.id, first.board.id);
+    assert_eq!(Board::get_all(&client)?.len(), 1);
+    assert_eq!(Label::get_all(&client, &first.board.id)?.len(), 8);
+    Ok(())
+}
+
+#[test]
+fn test_apply_adds_missing() -> Result<()> {
+    let client = FakeTrello::new().client();
+
+    let board = Board::create_with_defaults(&client, "Website", false, false)?;
+    Label::create(&client, &board.id, "Bug", Some(&LabelColor::Purple))?;
+    let backlog = List::create(&client, &board.id, "Backlog")?;
+    Card::create(
+        &client,
+        &backlog.id,
+        &Card::new("", "Add a contact form", "Existing", None, "", None),
+    )?;
+    List::create(&client, &board.id, "Ideas")?;
+
+    let template: BoardTemplate = TEMPLATE.parse()?;
+    let applied = template.apply(&client, false)?;
+
+    assert!(!applied.board_created);
+    assert_eq!(applied.board.id, board.id);
+    assert_eq!(
+        applied
+            .labels
+            .iter()
+            .map(|l| l.color.clone())
+            .collect::<Vec<Option<LabelColor>>>(),
+        vec![Some(LabelColor::Sky)]
+    );
+    assert_eq!(
+        applied
+            .lists
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["Done"]
+    );
+    assert_eq!(
+        applied
+            .cards
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["Write the about page"]
+    );
+    // the existing label keeps its color
+    assert_eq!(
+        applied.cards[0].labels.as_ref().unwrap()[0].color,
+        Some(LabelColor::Purple)
+    );
+
+    assert_eq!(
+        list_names(&client, &board.id)?,
+        vec!["Backlog", "Ideas", "Done"]
+    );
+    Ok(())
+}
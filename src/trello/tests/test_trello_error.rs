@@ -0,0 +1,68 @@
+use super::*;
+
+fn get_board_with_status(
+    board_id: &str,
+    status: usize,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> TrelloError {
+    let path = format!("/1/boards/{}?fields=id%2Cname%2Cclosed%2Curl", board_id);
+    let mut mock = mockito::mock("GET", path.as_str())
+        .with_status(status)
+        .with_body(body);
+    for (name, value) in headers {
+        mock = mock.with_header(name, value);
+    }
+    let _m = mock.create();
+
+    let mut config = ClientConfig::new(&mockito::server_url(), "some-token", "some-key");
+    config.retry = RetryPolicy::none();
+    let client = TrelloClient::new(config);
+
+    Board::get(&client, board_id).expect_err("Expected an error response")
+}
+
+#[test]
+fn test_unauthorized() {
+    let error = get_board_with_status("BOARD-401", 401, &[], "invalid token");
+
+    assert!(matches!(error, TrelloError::Unauthorized(m) if m == "invalid token"));
+}
+
+#[test]
+fn test_not_found() {
+    let error = get_board_with_status("BOARD-404", 404, &[], "");
+
+    assert!(matches!(error, TrelloError::NotFound(m) if m == "Not Found"));
+}
+
+#[test]
+fn test_rate_limited() {
+    let error = get_board_with_status("BOARD-429", 429, &[("retry-after", "10")], "");
+
+    assert!(matches!(
+        error,
+        TrelloError::RateLimited {
+            retry_after: Some(10)
+        }
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Rate limited by Trello, retry after 10 seconds"
+    );
+}
+
+#[test]
+fn test_api_error_json_message() {
+    let error = get_board_with_status(
+        "BOARD-400",
+        400,
+        &[("content-type", "application/json")],
+        &json!({"message": "invalid value for name", "error": "ERROR"}).to_string(),
+    );
+
+    assert!(matches!(
+        error,
+        TrelloError::Api { status: 400, message } if message == "invalid value for name"
+    ));
+}
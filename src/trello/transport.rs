@@ -0,0 +1,50 @@
+use crate::trello_error::TrelloError;
+
+use reqwest::blocking::{Client, Request, Response};
+use std::fmt::Debug;
+
+/// Executes the HTTP requests made by a TrelloClient. Requests are built with the
+/// reqwest client of the TrelloClient (and therefore already carry the Authorization
+/// header), so a Transport only needs to deliver them and return the response.
+///
+/// Status codes are interpreted by the TrelloClient, so a Transport should return
+/// unsuccessful responses as they are rather than converting them into errors.
+pub trait Transport: Debug + Send + Sync {
+    fn execute(&self, request: Request) -> Result<Response, TrelloError>;
+
+    /// Whether the transport refuses requests which modify objects
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// Whether any response was served from a cache rather than by Trello
+    fn served_from_cache(&self) -> bool {
+        false
+    }
+
+    /// Number of responses served from a cache rather than by Trello
+    fn cache_hits(&self) -> usize {
+        0
+    }
+
+    /// Discards cached responses, so that subsequent requests reach Trello
+    fn invalidate_cache(&self) {}
+}
+
+/// Transport which sends requests over the network
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: Client,
+}
+
+impl HttpTransport {
+    pub fn new(client: Client) -> HttpTransport {
+        HttpTransport { client }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn execute(&self, request: Request) -> Result<Response, TrelloError> {
+        Ok(self.client.execute(request)?)
+    }
+}
@@ -1,3 +1,7 @@
+use reqwest::StatusCode;
+use reqwest::blocking::Response;
+use reqwest::header::RETRY_AFTER;
+use serde::Deserialize;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,4 +14,71 @@ pub enum TrelloError {
     Io(#[from] std::io::Error),
     #[error("Card Parse Error: {0}")]
     CardParse(String),
+    #[error("Unauthorized: {0}. Please check your credentials or run 'tro setup'")]
+    Unauthorized(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error(
+        "Rate limited by Trello{}",
+        .retry_after.map(|s| format!(", retry after {} seconds", s)).unwrap_or_default()
+    )]
+    RateLimited { retry_after: Option<u64> },
+    #[error("Trello API error ({status}): {message}")]
+    Api { status: u16, message: String },
+    #[error("Export error: {0}")]
+    Export(String),
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+    #[error("Label color error: {0}")]
+    LabelColor(String),
+    #[error("Template error: {0}")]
+    Template(String),
+    #[error("Offline export: {0}")]
+    Offline(String),
+    #[error("Authorization error: {0}")]
+    Authorize(String),
+    #[error("Attachment error: {0}")]
+    Attachment(String),
+}
+
+// Trello returns errors either as plain text or as a json object
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+impl TrelloError {
+    /// Classifies an unsuccessful response from Trello based on its status code,
+    /// using the response body as the error message.
+    pub(crate) fn from_response(response: Response) -> TrelloError {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok());
+
+        let body = response.text().unwrap_or_default();
+        let message = match serde_json::from_str::<ErrorBody>(&body) {
+            Ok(e) => e.message,
+            Err(_) => body.trim().to_string(),
+        };
+        let message = match message.is_empty() {
+            true => status
+                .canonical_reason()
+                .unwrap_or("Unknown error")
+                .to_string(),
+            false => message,
+        };
+
+        match status {
+            StatusCode::UNAUTHORIZED => TrelloError::Unauthorized(message),
+            StatusCode::NOT_FOUND => TrelloError::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => TrelloError::RateLimited { retry_after },
+            _ => TrelloError::Api {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
 }